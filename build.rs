@@ -1,62 +1,206 @@
 use ::std::{env, fs::File, io, path::Path};
 
-#[derive(serde::Deserialize, Hash)]
-#[serde(rename_all = "camelCase")]
-pub struct MapZone {
-    #[serde(rename = "@other")]
-    pub zone: String,
-    #[serde(rename = "@territory")]
-    pub territory: Option<String>,
-    #[serde(rename = "@type")]
-    pub iana: Vec<chrono_tz::Tz>,
-}
+#[path = "src/cldr.rs"]
+mod cldr;
 
-#[derive(serde::Deserialize, Hash)]
-#[serde(rename_all = "camelCase")]
-struct MapTimezones {
-    #[serde(rename = "@otherVersion")]
-    other_version: String,
-    #[serde(rename = "@typeVersion")]
-    type_version: String,
-    #[serde(rename = "$value")]
-    zones: Vec<MapZone>,
-}
+use cldr::{MapZone, WindowsZonesData};
 
-#[derive(serde::Deserialize, Hash)]
-#[serde(rename_all = "camelCase")]
-pub struct WindowsZones {
-    #[serde(rename = "$value")]
-    timezones: MapTimezones,
-}
+impl WindowsZonesData {
+    /// Pinned release of the Unicode CLDR.
+    const RELEASE: &'static str = "release-44";
 
-#[derive(serde::Deserialize, Hash)]
-#[serde(rename_all = "camelCase")]
-pub struct WindowsZonesData {
-    windows_zones: WindowsZones,
-}
+    /// Expected SHA-256 digest of the pinned dataset.
+    const SHA256: &'static str = "a7bb485ff97e225709f08f4fcc657b84c2589b3560f0e1af2289a40112ca6cbd";
 
-impl WindowsZonesData {
-    const SOURCE: &'static str = "https://raw.githubusercontent.com/unicode-org/cldr/main/common/supplemental/windowsZones.xml";
+    #[cfg(feature = "update-cldr")]
+    /// URL of the pinned dataset.
+    fn source() -> String {
+        format!(
+            "https://raw.githubusercontent.com/unicode-org/cldr/{}/common/supplemental/windowsZones.xml",
+            Self::RELEASE
+        )
+    }
+
+    /// Path of the vendored dataset, relative to the crate root.
+    const VENDORED: &'static str = "data/windowsZones.xml";
+
+    /// Path of the cached download in the user cache directory, keyed by release and digest.
+    #[cfg(feature = "update-cldr")]
+    fn cache_path() -> Option<std::path::PathBuf> {
+        use ::std::path::PathBuf;
+
+        println!("cargo:rerun-if-env-changed=SYSTEM_TZ_CACHE_DIR");
 
-    /// Download latest dataset from `Self::SOURCE`.
-    async fn get() -> Self {
-        use quick_xml::de::from_str;
+        let dir = env::var_os("SYSTEM_TZ_CACHE_DIR")
+            .or_else(|| env::var_os("XDG_CACHE_HOME"))
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
 
-        let request = reqwest::get(Self::SOURCE)
-            .await
-            .expect("Failed to GET Unicode CLDR data");
+        Some(dir.join("system_tz").join(format!(
+            "windowsZones-{}-{}.xml",
+            Self::RELEASE,
+            Self::SHA256.get(..16)?
+        )))
+    }
+
+    /// Number of download attempts before giving up.
+    #[cfg(feature = "update-cldr")]
+    const ATTEMPTS: u32 = 3;
 
-        let response = request
-            .text()
-            .await
-            .expect("Failed to decode UTF-8 from HTTP response");
+    /// Fetches the pinned dataset from `Self::source()`,
+    /// honoring `HTTPS_PROXY`/`HTTP_PROXY` and retrying with an exponential backoff.
+    #[cfg(feature = "update-cldr")]
+    fn fetch() -> Result<String, reqwest::Error> {
+        use ::std::time::Duration;
 
-        let mut data: Self = from_str(&response).expect("Failed to deserialize XML data");
+        let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(30));
+        let proxy_url = |vars: [&str; 2]| {
+            for var in vars {
+                println!("cargo:rerun-if-env-changed={var}");
+            }
+            vars.iter().find_map(|var| env::var(var).ok())
+        };
+        if let Some(url) = proxy_url(["HTTPS_PROXY", "https_proxy"]) {
+            builder = builder.proxy(reqwest::Proxy::https(url)?);
+        }
+        if let Some(url) = proxy_url(["HTTP_PROXY", "http_proxy"]) {
+            builder = builder.proxy(reqwest::Proxy::http(url)?);
+        }
+        let client = builder.build()?;
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Failed to start Tokio runtime");
+
+        runtime.block_on(async {
+            let mut attempt = 1;
+            loop {
+                let result = async {
+                    client
+                        .get(Self::source())
+                        .send()
+                        .await?
+                        .error_for_status()?
+                        .text()
+                        .await
+                }
+                .await;
+
+                match result {
+                    Err(_) if attempt < Self::ATTEMPTS => {
+                        tokio::time::sleep(Duration::from_secs(1 << attempt)).await;
+                        attempt += 1;
+                    }
+                    result => return result,
+                }
+            }
+        })
+    }
+
+    /// Downloads the pinned dataset, unless a valid copy is found in `Self::cache_path()`.
+    ///
+    /// On failure, emits a cargo warning with remediation steps and returns `None`.
+    #[cfg(feature = "update-cldr")]
+    fn download() -> Option<String> {
+        let cache = Self::cache_path();
+
+        if let Some(xml) = cache
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .filter(|xml| Self::digest(xml) == Self::SHA256)
+        {
+            return Some(xml);
+        }
+
+        let xml = match Self::fetch() {
+            Ok(xml) => xml,
+            Err(error) => {
+                println!(
+                    "cargo:warning=Failed to download CLDR `WindowsZones` dataset after {} attempts: {error}",
+                    Self::ATTEMPTS
+                );
+                println!(
+                    "cargo:warning=Falling back to the vendored dataset ({}). To fix the download:",
+                    Self::RELEASE
+                );
+                println!(
+                    "cargo:warning=  * set `HTTPS_PROXY` (or `HTTP_PROXY`) if behind a proxy;"
+                );
+                if let Some(path) = &cache {
+                    println!(
+                        "cargo:warning=  * or copy `{}` to `{}`;",
+                        Self::source(),
+                        path.display()
+                    );
+                }
+                println!("cargo:warning=  * or disable the `update-cldr` feature.");
+                return None;
+            }
+        };
+
+        // Caching is best effort, only valid data is written
+        if let Some(path) = cache.filter(|_| Self::digest(&xml) == Self::SHA256) {
+            if let Some(dir) = path.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            let _ = std::fs::write(path, &xml);
+        }
+
+        Some(xml)
+    }
+
+    /// Reads the vendored dataset from `Self::VENDORED`.
+    fn vendored() -> String {
+        println!("cargo:rerun-if-changed={}", Self::VENDORED);
+
+        let path = Path::new(
+            &env::var("CARGO_MANIFEST_DIR")
+                .expect("Failed to get `CARGO_MANIFEST_DIR` env variable"),
+        )
+        .join(Self::VENDORED);
+
+        std::fs::read_to_string(path).expect("Failed to read vendored Unicode CLDR data")
+    }
+
+    /// Returns the hexadecimal SHA-256 digest of the dataset.
+    fn digest(xml: &str) -> String {
+        use sha2::{Digest, Sha256};
+
+        Sha256::digest(xml.as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    /// Checks the integrity of the dataset against `Self::SHA256`.
+    fn verify(xml: &str) {
+        assert_eq!(
+            Self::digest(xml),
+            Self::SHA256,
+            "Integrity check failed for CLDR `WindowsZones` dataset ({})",
+            Self::RELEASE
+        );
+    }
+
+    /// Gets the dataset, either vendored or downloaded (with the `update-cldr` feature).
+    fn get() -> Self {
+        #[cfg(feature = "update-cldr")]
+        let response = Self::download().unwrap_or_else(Self::vendored);
+
+        #[cfg(not(feature = "update-cldr"))]
+        let response = Self::vendored();
+
+        Self::verify(&response);
+
+        let mut data = Self::from_xml(&response).expect("Failed to deserialize XML data");
+        data.retain_known_zones();
 
         for tz in [MapZone {
             zone: "Coordinated Universal Time".into(),
             territory: None,
-            iana: vec![chrono_tz::Etc::UTC],
+            iana: vec!["Etc/UTC".into()],
         }] {
             data.windows_zones.timezones.zones.push(tz)
         }
@@ -64,13 +208,23 @@ impl WindowsZonesData {
         data
     }
 
-    /// Returns the hash of the downloaded dataset.
-    fn hash(&self) -> u64 {
-        use ::std::hash::{Hash, Hasher};
+    /// Returns the build date, taken from `SOURCE_DATE_EPOCH` when set for reproducible builds.
+    fn build_date() -> chrono::DateTime<chrono::Utc> {
+        println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
 
-        let mut state = std::collections::hash_map::DefaultHasher::new();
-        Hash::hash(&self, &mut state);
-        Hasher::finish(&state)
+        env::var("SOURCE_DATE_EPOCH").map_or_else(
+            |_| chrono::Utc::now(),
+            |epoch| {
+                epoch
+                    .trim()
+                    .parse()
+                    .ok()
+                    .and_then(|secs| {
+                        chrono::TimeZone::timestamp_opt(&chrono::Utc, secs, 0).single()
+                    })
+                    .expect("Invalid `SOURCE_DATE_EPOCH` env variable")
+            },
+        )
     }
 
     /// Writes the `WINDOWS_ZONES_VERSION` static containing metadata regarding build and dataset.
@@ -79,67 +233,266 @@ impl WindowsZonesData {
 
         let msg = "Failed to write version to `BufWriter`";
 
-        writeln!(f, "#[cfg(windows)]").expect(msg);
         writeln!(f, "/// Version of the bundled CLDR `WindowsZones` dataset").expect(msg);
+        writeln!(f, "#[allow(clippy::unreadable_literal)]").expect(msg);
         writeln!(
             f,
-            "static WINDOWS_ZONES_VERSION: once_cell::sync::Lazy<WindowsZonesVersion> = once_cell::sync::Lazy::new(|| {{"
+            "static WINDOWS_ZONES_VERSION: WindowsZonesVersion = WindowsZonesVersion {{"
         )
         .expect(msg);
-        writeln!(f, "   WindowsZonesVersion {{",).expect(msg);
+        writeln!(f, "    release: {:?},", Self::RELEASE).expect(msg);
+        writeln!(f, "    build_date: {:?},", Self::build_date().to_rfc3339()).expect(msg);
         writeln!(
             f,
-            "       build_date: {:?}.parse().ok(),",
-            chrono::Utc::now().to_rfc3339()
+            "    version: ({:?}, {:?}),",
+            &self.windows_zones.timezones.other_version, &self.windows_zones.timezones.type_version,
         )
         .expect(msg);
+        // `Self::get()` only returns data matching the pinned digest
+        writeln!(f, "    sha256: {:?},", Self::SHA256).expect(msg);
+        writeln!(f, "}};").expect(msg);
+        writeln!(f).expect(msg);
+    }
+
+    /// Returns the `WindowsZone` variant name of a Windows `zone` name
+    /// (e.g. `UtcMinus02` for `UTC-02`).
+    fn variant(zone: &str) -> String {
+        zone.replace('+', " Plus ")
+            .replace('-', " Minus ")
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                // Acronyms are written in camel case (e.g. `Utc`, `Sa`)
+                let word = if word.bytes().all(|b| !b.is_ascii_lowercase()) {
+                    word.to_ascii_lowercase()
+                } else {
+                    word.to_owned()
+                };
+                let (first, rest) = word.split_at(1);
+                first.to_ascii_uppercase() + rest
+            })
+            .collect()
+    }
+
+    /// Writes a `WindowsZone` enum containing the known Windows zone names.
+    fn _write_enum(&self, f: &mut std::io::BufWriter<std::fs::File>) {
+        use ::std::{collections::BTreeMap, io::Write};
+
+        let msg = "Failed to write enum to `BufWriter`";
+
+        let mut variants = BTreeMap::new();
+        for MapZone { zone, .. } in &self.windows_zones.timezones.zones {
+            let variant = Self::variant(zone);
+            if let Some(other) = variants.insert(variant.clone(), zone) {
+                assert_eq!(
+                    other, zone,
+                    "Windows zones `{other}` and `{zone}` map to the same variant `{variant}`"
+                );
+            }
+        }
+
         writeln!(
             f,
-            "       version: ({:?}, {:?}),",
-            &self.windows_zones.timezones.other_version, &self.windows_zones.timezones.type_version,
+            "/// Known Microsoft Windows zone, as named in CLDR `WindowsZones` data"
+        )
+        .expect(msg);
+        writeln!(
+            f,
+            "#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]"
+        )
+        .expect(msg);
+        writeln!(f, "pub enum WindowsZone {{").expect(msg);
+        for (variant, zone) in &variants {
+            writeln!(f, "    /// `{zone}`").expect(msg);
+            writeln!(f, "    {variant},").expect(msg);
+        }
+        writeln!(
+            f,
+            "    /// Zone missing from the bundled dataset, loaded at runtime with `runtime-cldr`"
         )
         .expect(msg);
-        writeln!(f, "       hash: \"{}\".parse().ok()", self.hash()).expect(msg);
-        writeln!(f, "   }}",).expect(msg);
-        writeln!(f, "}});",).expect(msg);
+        writeln!(f, "    Other(&'static str),").expect(msg);
+        writeln!(f, "}}").expect(msg);
+        writeln!(f).expect(msg);
+
+        writeln!(f, "impl WindowsZone {{").expect(msg);
+        writeln!(f, "    /// All the Windows zones of the bundled dataset.").expect(msg);
+        writeln!(f, "    pub const ALL: &'static [Self] = &[").expect(msg);
+        for variant in variants.keys() {
+            writeln!(f, "        Self::{variant},").expect(msg);
+        }
+        writeln!(f, "    ];").expect(msg);
+        writeln!(f).expect(msg);
+        writeln!(f, "    #[must_use]").expect(msg);
+        writeln!(
+            f,
+            "    /// Returns the Windows zone name (e.g. `Romance Standard Time`)."
+        )
+        .expect(msg);
+        writeln!(f, "    #[allow(clippy::too_many_lines)]").expect(msg);
+        writeln!(f, "    pub const fn as_str(self) -> &'static str {{").expect(msg);
+        writeln!(f, "        match self {{").expect(msg);
+        for (variant, zone) in &variants {
+            writeln!(f, "            Self::{variant} => {zone:?},").expect(msg);
+        }
+        writeln!(f, "            Self::Other(zone) => zone,").expect(msg);
+        writeln!(f, "        }}").expect(msg);
+        writeln!(f, "    }}").expect(msg);
+        writeln!(f, "}}").expect(msg);
+        writeln!(f).expect(msg);
+
+        writeln!(f, "impl core::str::FromStr for WindowsZone {{").expect(msg);
+        writeln!(f, "    type Err = Error;").expect(msg);
+        writeln!(f).expect(msg);
+        writeln!(f, "    #[allow(clippy::too_many_lines)]").expect(msg);
+        writeln!(f, "    fn from_str(s: &str) -> Result<Self, Self::Err> {{").expect(msg);
+        writeln!(f, "        match s {{").expect(msg);
+        for (variant, zone) in &variants {
+            writeln!(f, "            {zone:?} => Ok(Self::{variant}),").expect(msg);
+        }
+        writeln!(f, "            _ => Err(Error::UnknownTimezone),").expect(msg);
+        writeln!(f, "        }}").expect(msg);
+        writeln!(f, "    }}").expect(msg);
+        writeln!(f, "}}").expect(msg);
+        writeln!(f).expect(msg);
+
+        writeln!(f, "impl core::fmt::Display for WindowsZone {{").expect(msg);
+        writeln!(
+            f,
+            "    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {{"
+        )
+        .expect(msg);
+        writeln!(f, "        f.write_str(self.as_str())").expect(msg);
+        writeln!(f, "    }}").expect(msg);
+        writeln!(f, "}}").expect(msg);
         writeln!(f).expect(msg);
     }
 
-    /// Writes a `WINDOWS_ZONES` static containing the downloaded data.
+    /// Writes a `WINDOWS_ZONES` static containing the downloaded data,
+    /// sorted by `(zone, territory)` and indexed by IANA name for binary searches.
     fn _write_data(&self, f: &mut std::io::BufWriter<std::fs::File>) {
         use ::std::io::Write;
 
         let msg = "Failed to write data to `BufWriter`";
 
-        //writeln!(f, "#[cfg(windows)]").expect(msg);
+        // `001` sorts before ISO 3166-1 codes, so the default mapping comes first for each zone
+        let mut zones: Vec<&MapZone> = self.windows_zones.timezones.zones.iter().collect();
+        zones.sort_by(|a, b| (&a.zone, &a.territory).cmp(&(&b.zone, &b.territory)));
+
+        let mut by_iana: Vec<(&str, usize)> = zones
+            .iter()
+            .enumerate()
+            .flat_map(|(index, zone)| zone.iana.iter().map(move |tz| (tz.as_str(), index)))
+            .collect();
+        by_iana.sort_unstable();
+
         writeln!(
             f,
             "/// Simplified representation of CLDR `WindowsZones` data"
         )
         .expect(msg);
-        writeln!(f, "static WINDOWS_ZONES: once_cell::sync::Lazy<Vec<WindowsTz>> = once_cell::sync::Lazy::new(|| {{").expect(msg);
-        writeln!(f, "   vec![").expect(msg);
+        writeln!(
+            f,
+            "static WINDOWS_ZONES: WindowsZonesIndex = WindowsZonesIndex {{"
+        )
+        .expect(msg);
+        writeln!(f, "    zones: alloc::borrow::Cow::Borrowed(&[").expect(msg);
         for MapZone {
             zone,
             territory,
             iana,
-        } in &self.windows_zones.timezones.zones
+        } in zones
         {
-            writeln!(f, "       WindowsTz {{").expect(msg);
-            writeln!(f, "           zone: {zone:#?},").expect(msg);
-            writeln!(f, "           territory: {territory:?},").expect(msg);
-            writeln!(f, "           iana: vec![").expect(msg);
+            writeln!(f, "        &WindowsTz {{").expect(msg);
+            writeln!(f, "            zone: WindowsZone::{},", Self::variant(zone)).expect(msg);
+            writeln!(f, "            territory: {territory:?},").expect(msg);
+            writeln!(f, "            iana: &[").expect(msg);
             for tz in iana {
-                writeln!(f, "               {:#?},", tz.name()).expect(msg);
+                writeln!(f, "                {:#?},", tz).expect(msg);
             }
-            writeln!(f, "           ]").expect(msg);
-            writeln!(f, "       }},").expect(msg);
+            writeln!(f, "            ],").expect(msg);
+            writeln!(f, "        }},").expect(msg);
         }
-        writeln!(f, "    ]").expect(msg);
-        writeln!(f, "}});").expect(msg);
+        writeln!(f, "    ]),").expect(msg);
+        writeln!(f, "    by_iana: alloc::borrow::Cow::Borrowed(&[").expect(msg);
+        for (name, index) in by_iana {
+            writeln!(f, "        ({name:?}, {index}),").expect(msg);
+        }
+        writeln!(f, "    ]),").expect(msg);
+        writeln!(f, "}};").expect(msg);
         writeln!(f).expect(msg);
     }
 
+    /// Gets the territory of each IANA timezone with its rank: `0` for the main zone
+    /// of a Windows zone (`001`), `1` for the main zone of a territory, `2` otherwise;
+    /// sorted by territory and timezone.
+    ///
+    /// CLDR keeps deprecated names (e.g. `Asia/Calcutta`), which are replaced by current ones.
+    fn territories(&self) -> Vec<(&str, String, u8)> {
+        let deprecated = DeprecatedNames::names();
+        let current = |name: &str| {
+            deprecated
+                .binary_search_by(|(x, _)| x.as_str().cmp(name))
+                .map_or_else(|_| name.to_owned(), |index| deprecated[index].1.clone())
+        };
+
+        let zones = &self.windows_zones.timezones.zones;
+        let golden: Vec<&str> = zones
+            .iter()
+            .filter(|x| x.territory.as_deref() == Some("001"))
+            .filter_map(|x| x.iana.first().map(String::as_str))
+            .collect();
+
+        let golden = &golden;
+        let mut territories: Vec<(&str, String, u8)> = zones
+            .iter()
+            .filter_map(|x| x.territory.as_deref().map(|territory| (territory, &x.iana)))
+            .filter(|(territory, _)| territory.bytes().all(|b| b.is_ascii_uppercase()))
+            .filter(|(territory, _)| *territory != "ZZ")
+            .flat_map(|(territory, iana)| {
+                iana.iter().enumerate().map(move |(index, tz)| {
+                    let rank = if golden.contains(&tz.as_str()) {
+                        0
+                    } else {
+                        u8::from(index > 0) + 1
+                    };
+                    (territory, current(tz), rank)
+                })
+            })
+            .collect();
+        territories.sort_unstable();
+        territories.dedup_by(|a, b| (a.0, &a.1) == (b.0, &b.1));
+        territories
+    }
+
+    /// Writes the `TZ_TERRITORIES` static to `path`.
+    fn build_territories<P: AsRef<Path>>(&self, path: P) {
+        use ::std::io::Write;
+
+        let msg = "Failed to write territories to `BufWriter`";
+
+        let out_dir = env::var("OUT_DIR").expect("Failed to get `OUT_DIR` env variable");
+        let out_path = Path::new(&out_dir).join(path.as_ref());
+        let target = File::create(out_path).expect("Failed to create file");
+        let mut f = io::BufWriter::new(target);
+
+        writeln!(
+            f,
+            "/// Territory (ISO 3166-1 alpha-2 code) of IANA timezones with their rank"
+        )
+        .expect(msg);
+        writeln!(
+            f,
+            "/// (`0` for main zones, `1` for main zones of a territory), sorted"
+        )
+        .expect(msg);
+        writeln!(f, "static TZ_TERRITORIES: &[(&str, &str, u8)] = &[").expect(msg);
+        for (territory, tz, rank) in self.territories() {
+            writeln!(f, "    ({territory:?}, {tz:?}, {rank}),").expect(msg);
+        }
+        writeln!(f, "];").expect(msg);
+    }
+
     /// Writes downloaded data to `path`.
     fn build<P: AsRef<Path>>(self, path: P) {
         let out_dir = env::var("OUT_DIR").expect("Failed to get `OUT_DIR` env variable");
@@ -147,13 +500,405 @@ impl WindowsZonesData {
         let target = File::create(out_path).expect("Failed to create file");
         let mut f = io::BufWriter::new(target);
         self._write_version(&mut f);
+        self._write_enum(&mut f);
         self._write_data(&mut f);
     }
 }
 
-#[tokio::main]
-async fn main() {
-    if env::var("CARGO_CFG_WINDOWS").is_ok() {
-        WindowsZonesData::get().await.build("windows_zones.rs")
+/// Current metazone (`to` attribute absent) of a timezone in CLDR `metaZones.xml`.
+#[cfg(feature = "localized-names")]
+#[derive(serde::Deserialize)]
+struct UsesMetazone {
+    #[serde(rename = "@mzone")]
+    mzone: String,
+    #[serde(rename = "@to")]
+    to: Option<String>,
+}
+
+#[cfg(feature = "localized-names")]
+#[derive(serde::Deserialize)]
+struct MetazoneTimezone {
+    #[serde(rename = "@type")]
+    iana: chrono_tz::Tz,
+    #[serde(rename = "usesMetazone", default)]
+    metazones: Vec<UsesMetazone>,
+}
+
+#[cfg(feature = "localized-names")]
+#[derive(serde::Deserialize)]
+struct MetazoneInfo {
+    #[serde(rename = "timezone", default)]
+    timezones: Vec<MetazoneTimezone>,
+}
+
+#[cfg(feature = "localized-names")]
+#[derive(serde::Deserialize)]
+struct MetaZones {
+    #[serde(rename = "metazoneInfo")]
+    info: MetazoneInfo,
+}
+
+#[cfg(feature = "localized-names")]
+#[derive(serde::Deserialize)]
+/// Root of the `metaZones.xml` document.
+struct MetaZonesData {
+    #[serde(rename = "metaZones")]
+    meta_zones: MetaZones,
+}
+
+/// Long names of a metazone in a CLDR locale document.
+#[cfg(feature = "localized-names")]
+#[derive(serde::Deserialize, Default)]
+struct LongNames {
+    generic: Option<String>,
+    standard: Option<String>,
+    daylight: Option<String>,
+}
+
+#[cfg(feature = "localized-names")]
+#[derive(serde::Deserialize)]
+struct Metazone {
+    #[serde(rename = "@type")]
+    name: String,
+    #[serde(default)]
+    long: LongNames,
+}
+
+#[cfg(feature = "localized-names")]
+#[derive(serde::Deserialize)]
+struct TimeZoneNames {
+    #[serde(rename = "metazone", default)]
+    metazones: Vec<Metazone>,
+}
+
+#[cfg(feature = "localized-names")]
+#[derive(serde::Deserialize)]
+struct Dates {
+    #[serde(rename = "timeZoneNames")]
+    names: TimeZoneNames,
+}
+
+#[cfg(feature = "localized-names")]
+#[derive(serde::Deserialize)]
+/// Root of a CLDR locale document (e.g. `de.xml`).
+struct Ldml {
+    dates: Dates,
+}
+
+#[cfg(feature = "localized-names")]
+impl MetaZonesData {
+    /// Path of the vendored metazones, relative to the crate root.
+    const VENDORED: &'static str = "data/metaZones.xml";
+
+    /// Path of the directory of vendored locales, relative to the crate root.
+    const LOCALES: &'static str = "data/timeZoneNames";
+
+    /// Reads a vendored document.
+    fn read<P: AsRef<Path>>(path: P) -> String {
+        println!("cargo:rerun-if-changed={}", path.as_ref().display());
+
+        let path = Path::new(
+            &env::var("CARGO_MANIFEST_DIR")
+                .expect("Failed to get `CARGO_MANIFEST_DIR` env variable"),
+        )
+        .join(path);
+
+        std::fs::read_to_string(path).expect("Failed to read vendored Unicode CLDR data")
+    }
+
+    /// Gets the current metazone of each timezone, sorted by IANA name.
+    fn metazones() -> Vec<(&'static str, String)> {
+        let data: Self =
+            quick_xml::de::from_str(&cldr::without_doctype(&Self::read(Self::VENDORED)))
+                .expect("Failed to deserialize XML data");
+
+        let mut metazones: Vec<(&'static str, String)> = data
+            .meta_zones
+            .info
+            .timezones
+            .into_iter()
+            .filter_map(|tz| {
+                let current = tz.metazones.into_iter().find(|x| x.to.is_none())?;
+                Some((tz.iana.name(), current.mzone))
+            })
+            .collect();
+        metazones.sort_unstable();
+        metazones
+    }
+
+    /// Gets the long names of the metazones for each vendored locale,
+    /// sorted by locale (file stem, e.g. `de`) and metazone.
+    fn names() -> Vec<(String, String, LongNames)> {
+        println!("cargo:rerun-if-changed={}", Self::LOCALES);
+
+        let dir = Path::new(
+            &env::var("CARGO_MANIFEST_DIR")
+                .expect("Failed to get `CARGO_MANIFEST_DIR` env variable"),
+        )
+        .join(Self::LOCALES);
+
+        let mut names = Vec::new();
+        for entry in std::fs::read_dir(dir).expect("Failed to read vendored locales") {
+            let path = entry.expect("Failed to read vendored locale").path();
+            let Some(locale) = path.file_stem().and_then(|x| x.to_str()).map(String::from) else {
+                continue;
+            };
+
+            let ldml: Ldml = quick_xml::de::from_str(&cldr::without_doctype(&Self::read(&path)))
+                .expect("Failed to deserialize XML data");
+            for metazone in ldml.dates.names.metazones {
+                names.push((locale.clone(), metazone.name, metazone.long));
+            }
+        }
+        names.sort_unstable_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        names
+    }
+
+    /// Writes the `METAZONES` and `METAZONE_NAMES` statics to `path`.
+    fn build<P: AsRef<Path>>(path: P) {
+        use ::std::io::Write;
+
+        let msg = "Failed to write metazones to `BufWriter`";
+
+        let out_dir = env::var("OUT_DIR").expect("Failed to get `OUT_DIR` env variable");
+        let out_path = Path::new(&out_dir).join(path.as_ref());
+        let target = File::create(out_path).expect("Failed to create file");
+        let mut f = io::BufWriter::new(target);
+
+        writeln!(
+            f,
+            "/// Current CLDR metazone of timezones, sorted by IANA name"
+        )
+        .expect(msg);
+        writeln!(f, "static METAZONES: &[(&str, &str)] = &[").expect(msg);
+        for (iana, metazone) in Self::metazones() {
+            writeln!(f, "    ({iana:?}, {metazone:?}),").expect(msg);
+        }
+        writeln!(f, "];").expect(msg);
+        writeln!(f).expect(msg);
+
+        writeln!(
+            f,
+            "/// Long names of CLDR metazones, sorted by locale and metazone"
+        )
+        .expect(msg);
+        writeln!(f, "static METAZONE_NAMES: &[(&str, &str, TzNames)] = &[").expect(msg);
+        for (locale, metazone, long) in Self::names() {
+            writeln!(f, "    ({locale:?}, {metazone:?}, TzNames {{").expect(msg);
+            writeln!(f, "        generic: {:?},", long.generic).expect(msg);
+            writeln!(f, "        standard: {:?},", long.standard).expect(msg);
+            writeln!(f, "        daylight: {:?},", long.daylight).expect(msg);
+            writeln!(f, "    }}),").expect(msg);
+        }
+        writeln!(f, "];").expect(msg);
+    }
+}
+
+/// Deprecated names of the IANA Time Zone Database.
+struct DeprecatedNames;
+
+impl DeprecatedNames {
+    /// Path of the vendored `backward` file of the tzdb release used by `chrono-tz`,
+    /// relative to the crate root.
+    const VENDORED: &'static str = "data/backward";
+
+    /// Section of `backward` listing locations merged into another zone,
+    /// whose names remain current.
+    const MERGED: &'static str = "# Pre-2013 practice";
+
+    /// Names kept although `backward` lists them, as they are the usual spelling.
+    const KEPT: &'static [&'static str] = &["UTC"];
+
+    /// Gets the names of the backward compatibility links along with their current name,
+    /// sorted.
+    fn names() -> Vec<(String, String)> {
+        println!("cargo:rerun-if-changed={}", Self::VENDORED);
+
+        let path = Path::new(
+            &env::var("CARGO_MANIFEST_DIR")
+                .expect("Failed to get `CARGO_MANIFEST_DIR` env variable"),
+        )
+        .join(Self::VENDORED);
+        let data = std::fs::read_to_string(path).expect("Failed to read vendored tzdb data");
+
+        // Sections are headed by a comment following two blank lines
+        let mut section = "";
+        let mut blanks = 0;
+        let mut names = Vec::new();
+        for line in data.lines() {
+            if blanks >= 2 && line.starts_with("# ") {
+                section = line;
+            }
+            blanks = if line.is_empty() { blanks + 1 } else { 0 };
+
+            let mut fields = line.split_whitespace();
+            if fields.next() != Some("Link") || section.starts_with(Self::MERGED) {
+                continue;
+            }
+            let (Some(target), Some(name)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            if Self::KEPT.contains(&name) {
+                continue;
+            }
+            // `#= TARGET1` is the intended target, when links to links are supported
+            let intended = (fields.next() == Some("#="))
+                .then(|| fields.next())
+                .flatten();
+            names.push((
+                name.to_owned(),
+                target.to_owned(),
+                intended.map(str::to_owned),
+            ));
+        }
+
+        let deprecated: Vec<String> = names.iter().map(|(name, ..)| name.clone()).collect();
+        let mut names: Vec<(String, String)> = names
+            .into_iter()
+            .map(|(name, target, intended)| {
+                let target = intended
+                    .filter(|intended| !deprecated.contains(intended))
+                    .unwrap_or(target);
+                (name, target)
+            })
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Writes the `DEPRECATED_NAMES` static to `path`.
+    fn build<P: AsRef<Path>>(path: P) {
+        use ::std::io::Write;
+
+        let msg = "Failed to write deprecated names to `BufWriter`";
+
+        let out_dir = env::var("OUT_DIR").expect("Failed to get `OUT_DIR` env variable");
+        let out_path = Path::new(&out_dir).join(path.as_ref());
+        let target = File::create(out_path).expect("Failed to create file");
+        let mut f = io::BufWriter::new(target);
+
+        writeln!(
+            f,
+            "/// Deprecated IANA timezone names along with their current name, sorted"
+        )
+        .expect(msg);
+        writeln!(f, "static DEPRECATED_NAMES: &[(&str, &str)] = &[").expect(msg);
+        for (name, target) in Self::names() {
+            writeln!(f, "    ({name:?}, {target:?}),").expect(msg);
+        }
+        writeln!(f, "];").expect(msg);
+    }
+}
+
+/// Abbreviations of the IANA Time Zone Database.
+struct Abbreviations;
+
+impl Abbreviations {
+    /// Year whose offsets are sampled, pinned for reproducible builds.
+    const YEAR: i32 = 2024;
+
+    /// Gets the alphabetic abbreviations used by current timezones during [`Self::YEAR`]
+    /// along with their UTC offset (in seconds), sorted by abbreviation, offset and timezone.
+    fn abbreviations() -> Vec<(String, i32, &'static str)> {
+        use chrono::{Offset, TimeZone};
+        use chrono_tz::OffsetName;
+
+        let deprecated: Vec<String> = DeprecatedNames::names()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+
+        let mut abbreviations = Vec::new();
+        for tz in chrono_tz::TZ_VARIANTS {
+            // Legacy POSIX-style zones (e.g. `CST6CDT`) duplicate the geographic ones
+            let legacy = !tz.name().contains('/') && tz.name() != "UTC";
+            if legacy || deprecated.iter().any(|name| name == tz.name()) {
+                continue;
+            }
+            for month in 1..=12 {
+                let date = chrono::NaiveDate::from_ymd_opt(Self::YEAR, month, 15)
+                    .expect("Failed to build sample date");
+                let offset = tz.offset_from_utc_date(&date);
+                let abbreviation = offset.abbreviation();
+                if abbreviation.chars().all(|c| c.is_ascii_alphabetic()) {
+                    abbreviations.push((
+                        abbreviation.to_owned(),
+                        offset.fix().local_minus_utc(),
+                        tz.name(),
+                    ));
+                }
+            }
+        }
+        abbreviations.sort_unstable();
+        abbreviations.dedup();
+        abbreviations
+    }
+
+    /// Writes the `TZ_ABBREVIATIONS` static to `path`.
+    fn build<P: AsRef<Path>>(path: P) {
+        use ::std::io::Write;
+
+        let msg = "Failed to write abbreviations to `BufWriter`";
+
+        let out_dir = env::var("OUT_DIR").expect("Failed to get `OUT_DIR` env variable");
+        let out_path = Path::new(&out_dir).join(path.as_ref());
+        let target = File::create(out_path).expect("Failed to create file");
+        let mut f = io::BufWriter::new(target);
+
+        writeln!(
+            f,
+            "/// Abbreviations used by timezones in {} with their UTC offset (in seconds), sorted",
+            Self::YEAR
+        )
+        .expect(msg);
+        writeln!(f, "static TZ_ABBREVIATIONS: &[(&str, i32, &str)] = &[").expect(msg);
+        for (abbreviation, offset, tz) in Self::abbreviations() {
+            writeln!(f, "    ({abbreviation:?}, {offset}, {tz:?}),").expect(msg);
+        }
+        writeln!(f, "];").expect(msg);
     }
 }
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/cldr.rs");
+
+    // `cfg!(windows)` would refer to the host running the build script,
+    // whereas the data is needed whenever the *target* is windows (e.g. cross-compilation)
+    let target_family = env::var("CARGO_CFG_TARGET_FAMILY").unwrap_or_default();
+    let windows_zones = WindowsZonesData::get();
+    windows_zones.build_territories("territories.rs");
+    if target_family.split(',').any(|family| family == "windows")
+        || env::var_os("CARGO_FEATURE_WINDOWS_ZONES").is_some()
+    {
+        windows_zones.build("windows_zones.rs")
+    }
+
+    DeprecatedNames::build("deprecated_names.rs");
+    Abbreviations::build("abbreviations.rs");
+
+    #[cfg(feature = "localized-names")]
+    MetaZonesData::build("metazones.rs");
+
+    #[cfg(feature = "capi")]
+    capi_header("system_tz.h");
+}
+
+#[cfg(feature = "capi")]
+/// Generates the C header of the `capi` module at `path`, relative to `OUT_DIR`.
+///
+/// The committed `include/system_tz.h` is generated with the `cbindgen` CLI instead,
+/// as build scripts shouldn't write outside of `OUT_DIR`: the tests check it is up to date.
+fn capi_header(path: &str) {
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=src/lib.rs");
+
+    let root = env::var("CARGO_MANIFEST_DIR").expect("Missing CARGO_MANIFEST_DIR");
+    let config = cbindgen::Config::from_root_or_default(&root);
+    cbindgen::Builder::new()
+        .with_crate(&root)
+        .with_config(config)
+        .generate()
+        .expect("Failed to generate the C header")
+        .write_to_file(Path::new(&env::var("OUT_DIR").expect("Missing OUT_DIR")).join(path));
+}
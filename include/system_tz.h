@@ -0,0 +1,27 @@
+#ifndef SYSTEM_TZ_H
+#define SYSTEM_TZ_H
+
+/* Generated by cbindgen from `src/lib.rs`, do not edit. */
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Returns the IANA name of the system timezone (e.g. `Europe/Paris`),
+// or `NULL` if it can't be detected.
+//
+// The string must be released with `system_tz_free()`.
+char *system_tz_name(void);
+
+// Releases a string returned by `system_tz_name()`.
+//
+// # Safety
+//
+// `name` must be `NULL` or a string returned by `system_tz_name()` not released yet.
+void system_tz_free(char *name);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SYSTEM_TZ_H */
@@ -1,12 +1,780 @@
-fn main() {
-    use system_tz::SystemTz;
-    if let Some(tz) = chrono_tz::Tz::system_tz() {
-        println!("{tz}");
-    } else {
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: tz [--json] [--watch]
+       tz doctor
+       tz offset [--at <datetime>]
+       tz abbr [--at <datetime>]
+       tz now [--format <format>]
+       tz convert <datetime> <from> [<to>]
+       tz list [--country <code>]
+       tz search <query>
+       tz windows <zone> [--reverse]
+       tz validate <name> [--strict]
+       tz posix [<zone>]
+
+Prints the timezone of the operating system (e.g. `Europe/Paris`).
+
+Commands:
+  doctor      Runs every probe and suggests fixes when the detection fails
+  offset      Prints the UTC offset of the timezone (e.g. `+01:00`)
+  abbr        Prints the abbreviation of the timezone (e.g. `CET`)
+  now         Prints the current date and time in the timezone
+  convert     Converts a date and time from a timezone to another one
+              (by default, the timezone of the operating system)
+  list        Lists the current IANA timezones, optionally of a country
+  search      Searches timezones by approximate name or city, best matches first
+  windows     Translates a Windows zone (e.g. `Romance Standard Time`) to an IANA name,
+              or the other way around with `--reverse`
+  validate    Checks that a name is a valid IANA timezone, exiting with an error otherwise
+              (or if it's a deprecated alias, with `--strict`)
+  posix       Prints the POSIX `TZ` rule of the timezone (by default, the timezone
+              of the operating system), derived from the current year and only
+              guaranteed to be valid for it
+
+Options:
+  --json      Prints the timezone along with detection metadata as JSON
+  --watch     Keeps running, printing a new line whenever the timezone changes
+  --at        Uses the given date and time instead of now, either in the timezone
+              (e.g. `2024-03-10 09:00`) or with an offset (RFC 3339)
+  --country   Filters by country (ISO 3166-1 alpha-2 code, e.g. `FR`)
+  --format    Formats the date and time as `rfc3339` (by default), `rfc2822`,
+              or with strftime specifiers (e.g. `%H:%M %Z`)
+  -h, --help  Prints this help
+";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.first().map(String::as_str) {
+        Some("doctor") => doctor(&args[1..]),
+        Some("offset") => offset(&args[1..]),
+        Some("abbr") => abbr(&args[1..]),
+        Some("now") => now(&args[1..]),
+        Some("convert") => convert(&args[1..]),
+        Some("list") => list(&args[1..]),
+        Some("search") => search(&args[1..]),
+        Some("windows") => windows(&args[1..]),
+        Some("validate") => validate(&args[1..]),
+        Some("posix") => posix(&args[1..]),
+        _ => detect(&args),
+    }
+}
+
+/// Prints the error of an unexpected argument, along with the usage.
+fn unexpected(arg: &str) -> ExitCode {
+    eprintln!("Error: Unexpected argument `{arg}`\n\n{USAGE}");
+    ExitCode::from(2)
+}
+
+/// Prints an error about the arguments, along with the usage.
+fn invalid(error: &str) -> ExitCode {
+    eprintln!("Error: {error}\n\n{USAGE}");
+    ExitCode::from(2)
+}
+
+/// Prints the hint to report a detection failure.
+fn report_failure() {
+    eprintln!(
+        "You might want to report this error on {}",
+        env!("CARGO_PKG_REPOSITORY")
+    );
+}
+
+/// `tz [--json] [--watch]`: prints the system timezone.
+fn detect(args: &[String]) -> ExitCode {
+    let mut json = false;
+    let mut watch = false;
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--watch" => watch = true,
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ => return unexpected(arg),
+        }
+    }
+
+    if watch {
+        return watch_detection(json);
+    }
+
+    let detected = system_tz::system_tz_with_source();
+    if json {
+        println!("{}", detection_json(detected));
+    }
+    match detected {
+        Some((tz, _)) => {
+            if !json {
+                println!("{tz}");
+            }
+            ExitCode::SUCCESS
+        }
+        None => {
+            eprintln!("Error: Failed to get timezone");
+            eprintln!("Run `tz doctor` for details");
+            report_failure();
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(any(
+    target_family = "unix",
+    target_family = "windows",
+    all(target_family = "wasm", feature = "js")
+))]
+/// `tz --watch`: prints the system timezone, then a new line whenever it changes,
+/// until stdout is closed.
+fn watch_detection(json: bool) -> ExitCode {
+    use std::io::Write;
+
+    let Some((_watcher, changes)) = system_tz::watch_channel() else {
+        eprintln!("Error: Failed to watch the timezone");
+        return ExitCode::FAILURE;
+    };
+
+    let mut stdout = std::io::stdout();
+    let mut print = || {
+        let detected = system_tz::system_tz_with_source();
+        if json {
+            writeln!(stdout, "{}", detection_json(detected))
+        } else if let Some((tz, _)) = detected {
+            writeln!(stdout, "{tz}")
+        } else {
+            eprintln!("Error: Failed to get timezone");
+            Ok(())
+        }
+    };
+
+    if print().is_err() {
+        return ExitCode::SUCCESS;
+    }
+    // The detection runs again, so that the line holds the source of the new timezone.
+    while changes.recv().is_ok() {
+        if print().is_err() {
+            break;
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(not(any(
+    target_family = "unix",
+    target_family = "windows",
+    all(target_family = "wasm", feature = "js")
+)))]
+/// `tz --watch`: unsupported without a watcher on this platform.
+fn watch_detection(_json: bool) -> ExitCode {
+    eprintln!("Error: Watching the timezone isn't supported on this platform");
+    ExitCode::FAILURE
+}
+
+/// Returns the system timezone, or prints why it failed.
+fn detected_tz() -> Result<chrono_tz::Tz, ExitCode> {
+    system_tz::system_tz().ok_or_else(|| {
         eprintln!("Error: Failed to get timezone");
+        eprintln!("Run `tz doctor` for details");
+        report_failure();
+        ExitCode::FAILURE
+    })
+}
+
+/// Parses the options of subcommands reading a point in time, i.e. `--at <datetime>`.
+fn at_option(args: &[String]) -> Result<Option<&str>, ExitCode> {
+    let mut at = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--at" => match args.next() {
+                Some(value) => at = Some(value.as_str()),
+                None => return Err(invalid("Missing value of `--at`")),
+            },
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                return Err(ExitCode::SUCCESS);
+            }
+            _ => match arg.strip_prefix("--at=") {
+                Some(value) => at = Some(value),
+                None => return Err(unexpected(arg)),
+            },
+        }
+    }
+    Ok(at)
+}
+
+/// Returns the instant of `--at <datetime>` in `tz`, or now.
+fn instant(tz: chrono_tz::Tz, at: Option<&str>) -> Result<chrono::DateTime<chrono::Utc>, ExitCode> {
+    at.map_or_else(
+        || Ok(chrono::Utc::now()),
+        |at| parse_datetime(tz, at).map_err(|error| invalid(&error)),
+    )
+}
+
+/// Parses a date and time, either with an offset (RFC 3339) or local to `tz`,
+/// warning about local times which are ambiguous or skipped by a DST transition.
+fn parse_datetime(tz: chrono_tz::Tz, value: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    use chrono::{LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc};
+
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+    let local = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .map(|date| date.and_time(NaiveTime::MIN))
+    })
+    .ok_or_else(|| {
+        format!(
+            "Invalid date and time `{value}` (e.g. `2024-03-10 09:00` or `2024-03-10T09:00:00+01:00`)"
+        )
+    })?;
+
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(datetime) => Ok(datetime.with_timezone(&Utc)),
+        LocalResult::Ambiguous(earliest, latest) => {
+            eprintln!(
+                "Warning: `{value}` is ambiguous in {tz}, as it's repeated by a DST transition: \
+                 using {} rather than {}",
+                earliest.to_rfc3339(),
+                latest.to_rfc3339()
+            );
+            Ok(earliest.with_timezone(&Utc))
+        }
+        LocalResult::None => {
+            // Shifts the time forward by the length of the gap, with the offset before it.
+            let before = tz
+                .offset_from_utc_datetime(&(local - chrono::Duration::days(1)))
+                .fix();
+            let utc = Utc.from_utc_datetime(&(local - before));
+            eprintln!(
+                "Warning: `{value}` doesn't exist in {tz}, as it's skipped by a DST transition: \
+                 using {}",
+                utc.with_timezone(&tz).to_rfc3339()
+            );
+            Ok(utc)
+        }
+    }
+}
+
+/// `tz offset [--at <datetime>]`: prints the UTC offset of the system timezone.
+fn offset(args: &[String]) -> ExitCode {
+    let result = at_option(args).and_then(|at| {
+        let tz = detected_tz()?;
+        let utc = instant(tz, at)?;
+        println!("{}", system_tz::SystemTzInfo::new(tz).offset_at(utc));
+        Ok(())
+    });
+    result.err().unwrap_or(ExitCode::SUCCESS)
+}
+
+/// `tz abbr [--at <datetime>]`: prints the abbreviation of the system timezone.
+fn abbr(args: &[String]) -> ExitCode {
+    let result = at_option(args).and_then(|at| {
+        let tz = detected_tz()?;
+        let utc = instant(tz, at)?;
+        println!("{}", system_tz::SystemTzInfo::new(tz).abbreviation_at(utc));
+        Ok(())
+    });
+    result.err().unwrap_or(ExitCode::SUCCESS)
+}
+
+/// `tz now [--format <format>]`: prints the current date and time in the system timezone.
+fn now(args: &[String]) -> ExitCode {
+    use chrono::format::{Item, StrftimeItems};
+
+    let mut format = "rfc3339";
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next() {
+                Some(value) => format = value,
+                None => return invalid("Missing value of `--format`"),
+            },
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ => match arg.strip_prefix("--format=") {
+                Some(value) => format = value,
+                None => return unexpected(arg),
+            },
+        }
+    }
+
+    let items = StrftimeItems::new(format).collect::<Vec<_>>();
+    if items.contains(&Item::Error) {
+        return invalid(&format!("Invalid format `{format}`"));
+    }
+    let tz = match detected_tz() {
+        Ok(tz) => tz,
+        Err(code) => return code,
+    };
+
+    let now = chrono::Utc::now().with_timezone(&tz);
+    match format {
+        "rfc3339" => println!(
+            "{}",
+            now.to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+        ),
+        "rfc2822" => println!("{}", now.to_rfc2822()),
+        _ => println!("{}", now.format_with_items(items.into_iter())),
+    }
+    ExitCode::SUCCESS
+}
+
+/// `tz convert <datetime> <from> [<to>]`: converts a date and time between timezones.
+fn convert(args: &[String]) -> ExitCode {
+    let zone = |name: &str| {
+        system_tz::ParseMode::Lenient
+            .parse(name)
+            .ok_or_else(|| invalid(&format!("Unknown timezone `{name}`")))
+    };
+    let result = match args {
+        [arg, ..] if arg == "-h" || arg == "--help" => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        [datetime, from] => zone(from).and_then(|from| Ok((datetime, from, detected_tz()?))),
+        [datetime, from, to] => zone(from).and_then(|from| zone(to).map(|to| (datetime, from, to))),
+        [_, _, _, arg, ..] => Err(unexpected(arg)),
+        _ => Err(invalid("Missing the date and time, or its timezone")),
+    }
+    .and_then(|(datetime, from, to)| {
+        let utc = parse_datetime(from, datetime).map_err(|error| invalid(&error))?;
+        println!("{}", utc.with_timezone(&to).format("%Y-%m-%d %H:%M:%S %Z"));
+        Ok(())
+    });
+    result.err().unwrap_or(ExitCode::SUCCESS)
+}
+
+/// `tz list [--country <code>]`: lists the current IANA timezones.
+fn list(args: &[String]) -> ExitCode {
+    let mut country = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--country" => match args.next() {
+                Some(value) => country = Some(value.as_str()),
+                None => return invalid("Missing value of `--country`"),
+            },
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ => match arg.strip_prefix("--country=") {
+                Some(value) => country = Some(value),
+                None => return unexpected(arg),
+            },
+        }
+    }
+
+    let zones = match country {
+        Some(country) => system_tz::territory_tz(country),
+        None => {
+            let mut zones = chrono_tz::TZ_VARIANTS
+                .iter()
+                .copied()
+                .filter(|tz| system_tz::normalize(*tz) == *tz)
+                .collect::<Vec<_>>();
+            zones.sort_unstable_by_key(|tz| tz.name());
+            zones
+        }
+    };
+    if zones.is_empty() {
         eprintln!(
-            "You might want to report this error on {}",
-            env!("CARGO_PKG_REPOSITORY")
+            "Error: No timezone in country `{}`",
+            country.unwrap_or_default()
         );
+        return ExitCode::FAILURE;
+    }
+    for tz in zones {
+        println!("{tz}");
+    }
+    ExitCode::SUCCESS
+}
+
+/// `tz search <query>`: prints the timezones matching `query`, best matches first.
+fn search(args: &[String]) -> ExitCode {
+    match args {
+        [] => return invalid("Missing the query"),
+        [arg] if arg == "-h" || arg == "--help" => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        _ => {}
+    }
+
+    // Unquoted words are searched together, e.g. `tz search new york`
+    let query = args.join(" ");
+    let found = system_tz::search_tz(&query);
+    if found.is_empty() {
+        eprintln!("Error: No timezone matches `{query}`");
+        return ExitCode::FAILURE;
+    }
+    for tz in found {
+        println!("{tz}");
+    }
+    ExitCode::SUCCESS
+}
+
+/// `tz windows <zone> [--reverse]`: translates between Windows zones and IANA names
+/// with the CLDR `WindowsZones` mapping.
+fn windows(args: &[String]) -> ExitCode {
+    use system_tz::{ParseTz, WindowsTz};
+
+    let mut zone = None;
+    let mut reverse = false;
+    for arg in args {
+        match arg.as_str() {
+            "--reverse" => reverse = true,
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ if zone.is_none() => zone = Some(arg.as_str()),
+            _ => return unexpected(arg),
+        }
+    }
+    let Some(zone) = zone else {
+        return invalid("Missing the zone");
+    };
+
+    let names = if reverse {
+        let mut names = Vec::new();
+        for windows in zone
+            .parse_tz()
+            .map(|tz| WindowsTz::from_iana(&tz))
+            .unwrap_or_default()
+        {
+            let name = windows.zone().to_string();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    } else {
+        WindowsTz::get(zone.trim(), None)
+            .and_then(|windows| windows.iana().first())
+            .map(|name| vec![(*name).to_owned()])
+            .unwrap_or_default()
+    };
+    if names.is_empty() {
+        eprintln!("Error: `{zone}` isn't mapped by CLDR `WindowsZones`");
+        return ExitCode::FAILURE;
+    }
+    for name in names {
+        println!("{name}");
+    }
+    ExitCode::SUCCESS
+}
+
+/// `tz validate <name> [--strict]`: checks that `name` is a valid IANA timezone.
+fn validate(args: &[String]) -> ExitCode {
+    use system_tz::ParseTz;
+
+    let mut name = None;
+    let mut strict = false;
+    for arg in args {
+        match arg.as_str() {
+            "--strict" => strict = true,
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ if name.is_none() => name = Some(arg.as_str()),
+            _ => return unexpected(arg),
+        }
+    }
+    let Some(name) = name else {
+        return invalid("Missing the name");
+    };
+
+    // Names are case-sensitive, as they are paths of the zoneinfo database
+    let Ok(tz) = name.parse::<chrono_tz::Tz>() else {
+        let suggestion = name.parse_tz().or_else(|| {
+            let city = name.rsplit('/').next().unwrap_or_default();
+            system_tz::search_tz(city).first().copied()
+        });
+        match suggestion {
+            Some(tz) => eprintln!("{name}: invalid, did you mean {tz}?"),
+            None => eprintln!("{name}: invalid"),
+        }
+        return ExitCode::FAILURE;
+    };
+
+    let canonical = system_tz::normalize(tz);
+    if canonical == tz {
+        println!("{tz}: valid, canonical");
+        ExitCode::SUCCESS
+    } else if strict {
+        eprintln!("{tz}: deprecated alias of {canonical}");
+        ExitCode::FAILURE
+    } else {
+        println!("{tz}: valid, alias of {canonical}");
+        ExitCode::SUCCESS
+    }
+}
+
+/// `tz posix [<zone>]`: prints the POSIX `TZ` rule of a timezone for the current year.
+fn posix(args: &[String]) -> ExitCode {
+    use chrono::Datelike;
+
+    let tz = match args {
+        [arg, ..] if arg == "-h" || arg == "--help" => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        [] => detected_tz(),
+        [name] => system_tz::ParseMode::Lenient
+            .parse(name)
+            .ok_or_else(|| invalid(&format!("Unknown timezone `{name}`"))),
+        [_, arg, ..] => Err(unexpected(arg)),
+    };
+    let tz = match tz {
+        Ok(tz) => tz,
+        Err(code) => return code,
+    };
+
+    let year = chrono::Utc::now().with_timezone(&tz).year();
+    if let Some(rule) = system_tz::to_posix_tz(tz, year) {
+        println!("{rule}");
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("Error: The rules of {tz} in {year} can't be expressed as a POSIX `TZ` string");
+        ExitCode::FAILURE
+    }
+}
+
+/// `tz doctor`: runs every probe, prints what each of them read and suggests fixes.
+fn doctor(args: &[String]) -> ExitCode {
+    use system_tz::{DetectionSource, Error};
+
+    match args.first().map(String::as_str) {
+        Some("-h" | "--help") => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Some(arg) => return unexpected(arg),
+        None => {}
+    }
+
+    let report = system_tz::ProviderChain::system().report_all();
+    println!(
+        "system_tz {} on {} ({})",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    print!("{report}");
+
+    let mut suggestions = Vec::new();
+    for probe in &report.probes {
+        match &probe.result {
+            Some(Err(Error::Parse { raw })) => suggestions.push(format!(
+                "`{raw}` read from {} isn't a known timezone: fix its spelling, \
+                 or update `tz` if the zone is newer than its database",
+                probe.probe
+            )),
+            Some(Err(Error::Unsupported { raw })) => suggestions.push(format!(
+                "`{raw}` read from {} isn't a timezone name (e.g. a path or a POSIX rule): \
+                 set an IANA name such as `Europe/Paris` instead",
+                probe.probe
+            )),
+            Some(Err(Error::NonCanonical)) => suggestions.push(format!(
+                "{} holds a deprecated or miscased name: use the canonical IANA name",
+                probe.probe
+            )),
+            Some(Err(err @ Error::PermissionDenied { .. })) => suggestions.push(format!(
+                "{} can't be read ({err}): grant read access to it",
+                probe.probe
+            )),
+            _ => {}
+        }
+    }
+
+    let detected = report.probes.iter().filter_map(|probe| match probe.result {
+        Some(Ok(tz)) => Some((probe.probe, tz)),
+        _ => None,
+    });
+    let disagreeing = detected
+        .clone()
+        .filter(|(_, tz)| Some(*tz) != report.tz)
+        .map(|(source, tz)| format!("{source} ({tz})"))
+        .collect::<Vec<_>>();
+    if let (Some(tz), false) = (report.tz, disagreeing.is_empty()) {
+        suggestions.push(format!(
+            "Sources disagree: {tz} takes precedence over {}",
+            disagreeing.join(", ")
+        ));
+    }
+    if report.confidence() == Some(system_tz::Confidence::Heuristic) {
+        suggestions.push(
+            "The timezone was only guessed from an abbreviation or an offset: configure it"
+                .to_owned(),
+        );
+    }
+    if detected
+        .clone()
+        .any(|(source, _)| source == DetectionSource::LocaltimeCopy)
+        && !detected
+            .clone()
+            .any(|(source, _)| source == DetectionSource::LocaltimeSymlink)
+    {
+        suggestions.push(
+            "/etc/localtime is a copy: link it into the zoneinfo database instead \
+             (e.g. `timedatectl set-timezone`), so that its name is known"
+                .to_owned(),
+        );
+    }
+    if report.tz.is_none() {
+        suggestions.push(if cfg!(target_family = "unix") {
+            "No timezone was detected: set the `TZ` environment variable (e.g. `TZ=Europe/Paris`), \
+             or link /etc/localtime into the zoneinfo database (e.g. `timedatectl set-timezone`)"
+        } else {
+            "No timezone was detected: set the `TZ` environment variable (e.g. `TZ=Europe/Paris`)"
+        }
+        .to_owned());
+    }
+
+    if suggestions.is_empty() {
+        println!("No issue found");
+    } else {
+        println!("Suggestions:");
+        for suggestion in &suggestions {
+            println!("- {suggestion}");
+        }
+    }
+
+    if report.tz.is_some() {
+        ExitCode::SUCCESS
+    } else {
+        report_failure();
+        ExitCode::FAILURE
+    }
+}
+
+/// Formats the detected timezone and its metadata as a JSON object,
+/// whose values are `null` if no timezone was detected.
+fn detection_json(detected: Option<(chrono_tz::Tz, system_tz::DetectionSource)>) -> String {
+    let string = |value: Option<&str>| value.map_or_else(|| "null".to_owned(), json_string);
+    let info = detected.map(|(tz, _)| system_tz::SystemTzInfo::new(tz));
+    let source = detected.map(|(_, source)| source);
+
+    let fields = [
+        ("tz", string(detected.map(|(tz, _)| tz.name()))),
+        ("source", string(source.map(system_tz::DetectionSource::id))),
+        (
+            "source_name",
+            string(source.map(system_tz::DetectionSource::name)),
+        ),
+        (
+            "confidence",
+            string(source.map(|source| source.confidence().name())),
+        ),
+        (
+            "offset",
+            string(
+                info.map(|info| info.current_offset().to_string())
+                    .as_deref(),
+            ),
+        ),
+        (
+            "abbreviation",
+            string(
+                info.map(system_tz::SystemTzInfo::current_abbreviation)
+                    .as_deref(),
+            ),
+        ),
+        (
+            "dst",
+            info.map_or_else(|| "null".to_owned(), |info| info.is_dst_now().to_string()),
+        ),
+        (
+            "next_transition",
+            string(
+                info.and_then(system_tz::SystemTzInfo::next_transition)
+                    .map(|transition| transition.to_rfc3339())
+                    .as_deref(),
+            ),
+        ),
+    ];
+
+    let fields = fields
+        .iter()
+        .map(|(key, value)| format!("{}:{value}", json_string(key)))
+        .collect::<Vec<_>>();
+    format!("{{{}}}", fields.join(","))
+}
+
+/// Formats `value` as a JSON string.
+fn json_string(value: &str) -> String {
+    use std::fmt::Write;
+
+    let mut json = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", u32::from(c));
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod test {
+    use super::{json_string, parse_datetime};
+
+    #[test]
+    fn it_parses_datetimes() {
+        let paris = chrono_tz::Europe::Paris;
+        let utc = |value: &str| value.parse::<chrono::DateTime<chrono::Utc>>().ok();
+
+        assert_eq!(
+            parse_datetime(paris, "2024-03-10T09:00:00-05:00").ok(),
+            utc("2024-03-10T14:00:00Z")
+        );
+        assert_eq!(
+            parse_datetime(paris, "2024-07-01 12:00").ok(),
+            utc("2024-07-01T10:00:00Z")
+        );
+        assert_eq!(
+            parse_datetime(paris, "2024-07-01").ok(),
+            utc("2024-06-30T22:00:00Z")
+        );
+
+        // The earliest of repeated times, and skipped times shifted by the gap
+        assert_eq!(
+            parse_datetime(paris, "2024-10-27 02:30").ok(),
+            utc("2024-10-27T00:30:00Z")
+        );
+        assert_eq!(
+            parse_datetime(paris, "2024-03-31 02:30").ok(),
+            utc("2024-03-31T01:30:00Z")
+        );
+
+        assert!(parse_datetime(paris, "tomorrow").is_err());
+        assert!(parse_datetime(paris, "2024-02-30").is_err());
+    }
+
+    #[test]
+    fn it_formats_json_strings() {
+        assert_eq!(json_string("Europe/Paris"), r#""Europe/Paris""#);
+        assert_eq!(json_string(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+        assert_eq!(json_string("\n\u{1b}"), r#""\u000a\u001b""#);
     }
 }
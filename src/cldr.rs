@@ -0,0 +1,67 @@
+//! Representation of the CLDR `WindowsZones` dataset,
+//! shared by the build script and the runtime loader.
+
+#[derive(serde::Deserialize, Hash)]
+#[serde(rename_all = "camelCase")]
+/// Mapping of a Windows zone (for a territory) to IANA zones.
+pub struct MapZone {
+    #[serde(rename = "@other")]
+    pub zone: String,
+    #[serde(rename = "@territory")]
+    pub territory: Option<String>,
+    #[serde(rename = "@type")]
+    pub iana: Vec<String>,
+}
+
+#[derive(serde::Deserialize, Hash)]
+#[serde(rename_all = "camelCase")]
+/// Versioned list of mappings.
+pub struct MapTimezones {
+    #[serde(rename = "@otherVersion")]
+    pub other_version: String,
+    #[serde(rename = "@typeVersion")]
+    pub type_version: String,
+    #[serde(rename = "$value")]
+    pub zones: Vec<MapZone>,
+}
+
+#[derive(serde::Deserialize, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowsZones {
+    #[serde(rename = "$value")]
+    pub timezones: MapTimezones,
+}
+
+#[derive(serde::Deserialize, Hash)]
+#[serde(rename_all = "camelCase")]
+/// Root of the `windowsZones.xml` document.
+pub struct WindowsZonesData {
+    pub windows_zones: WindowsZones,
+}
+
+impl WindowsZonesData {
+    /// Deserializes a `windowsZones.xml` document, keeping the IANA names as they are.
+    pub fn from_xml(xml: &str) -> Result<Self, quick_xml::DeError> {
+        quick_xml::de::from_str(&without_doctype(xml))
+    }
+
+    /// Skips the IANA names unknown to `chrono-tz` (e.g. from a newer tzdata),
+    /// as well as the mappings left without any.
+    pub fn retain_known_zones(&mut self) {
+        let zones = &mut self.windows_zones.timezones.zones;
+        for zone in zones.iter_mut() {
+            zone.iana
+                .retain(|name| name.parse::<chrono_tz::Tz>().is_ok());
+        }
+        zones.retain(|zone| !zone.iana.is_empty());
+    }
+}
+
+/// Removes the document type declaration of a CLDR document,
+/// which the deserializer doesn't expect.
+pub fn without_doctype(xml: &str) -> String {
+    xml.lines()
+        .filter(|line| !line.trim_start().starts_with("<!DOCTYPE"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -18,9 +18,29 @@
 //! [![Documentation](https://img.shields.io/docsrs/system_tz)](https://docs.rs/system_tz/latest/system_tz)
 //! [![License](https://img.shields.io/crates/l/system_tz.svg)](https://github.com/b4D8/system_tz/blob/main/LICENSE)
 //!
-//! This utility crate provides a single trait `SystemTz` which exposes the `system_tz()`
-//! method allowing to get the [timezone](https://en.wikipedia.org/wiki/Time_zone)
-//! from the operating system.
+//! This utility crate provides a `system_tz()` function allowing to get the
+//! [timezone](https://en.wikipedia.org/wiki/Time_zone) from the operating system
+//! (also exposed by the `SystemTz` trait, e.g. `chrono_tz::Tz::system_tz()`).
+//!
+//! ```rust
+//! # #[cfg(feature = "std")]
+//! if let Some(tz) = system_tz::system_tz() {
+//!     println!("{tz}");
+//! }
+//! ```
+//!
+//! When any timezone will do, `system_tz_or_utc()` falls back to UTC, and `SystemLocal`
+//! replaces `chrono::Local` with a `chrono::TimeZone` following the detected timezone,
+//! and `SystemTzCell` is a shared handle to it for long-running servers.
+//! `SystemTzInfo` tells whether daylight saving time is in effect, the current abbreviation
+//! and offset, and the next transition.
+//! `to_posix_tz()` turns a timezone into a POSIX `TZ` rule such as `CET-1CEST,M3.5.0,M10.5.0/3`,
+//! valid for a given year.
+//! `system_tz_for_user()` reads the timezone of another user, e.g. from a daemon running as root.
+//!
+//! The `SYSTEM_TZ` environment variable, or `set_override()`, forces the detected timezone
+//! (e.g. in containers or tests).
+//! `system_tz_with_confidence()` tells whether it was set explicitly, configured or guessed.
 //!
 //! Should support the following operating system families: `unix`, `windows` and `wasm`.
 //!
@@ -35,18 +55,96 @@
 //! On Microsoft Windows, because it uses of a special naming convention,
 //! the method relies on [`WindowsZones`](https://github.com/unicode-org/cldr/blob/main/common/supplemental/windowsZones.xml),
 //! a dataset maintained by the [Unicode Common Locale Data Repository (CLDR)](https://cldr.unicode.org/),
-//! which is downloaded and built into a static global object during compilation.
+//! which is vendored in the crate and built into a static global object during compilation
+//! (the `update-cldr` feature downloads it instead).
 //!
 //! ## Safety
 //!
 //! Attention was given to provide safe implementation (no `unwrap()` or `expect()`)
 //! but note that on windows target:
 //! * the build script is faillible (it is designed to panic on error)
-//! * 1 `unsafe` is used by the _fallback_ method because of [`windows`](https://crates.io/crates/windows) API.
+//! * `unsafe` is used by the _fallback_ methods because of [`windows`](https://crates.io/crates/windows) API.
+//!
+//! The C API (`capi` feature) is `unsafe` by nature: strings it returns must be released
+//! with `system_tz_free()`, exactly once. The Node-API addon (`napi` feature) also relies
+//! on `unsafe` to call the functions of Node.js, which are looked up at runtime.
+//!
+//! ## Cargo features
+//!
+//! Only the `std`, `js` and `probe-*` features are enabled by default:
+//!
+//! * `std`: detects the timezone from the operating system. Without it, the crate is `no_std`
+//!   (but requires `alloc`): the detection relies on custom providers added to
+//!   a `ProviderChain` (e.g. reading a configuration blob of an RTOS), while the parsing
+//!   (`ParseTz`), normalization and `WindowsZones` mapping remain available with `chrono-tz`,
+//!   including on bare-metal targets (e.g. `thumbv7em-none-eabihf`, checked in CI).
+//!   The other features relying on the operating system imply it.
+//! * `chrono-tz`: provides the `Tz` based API, implied by `std`. Without both, only the `tz-name`
+//!   feature remains useful.
+//! * `cli`: builds the `tz` binary (see [Command-line interface](#command-line-interface)),
+//!   implies `windows-zones` so that `tz windows` works on all platforms. It's left out of
+//!   the default features so that libraries don't compile the mapping for the binary.
+//! * `tz-name`: provides `system_tz_name()` without `std`, returning the raw IANA name
+//!   of the timezone (from `TZ`, `/etc/timezone`, `/etc/localtime` or the windows `Calendar`)
+//!   for users of other timezone crates, who don't want to compile `chrono-tz` nor its database.
+//!   As it reads the operating system, it requires a target providing the `std` crate.
+//! * `js`: detects the timezone with the JavaScript `Intl` API on `wasm` targets. Disable it
+//!   for non-browser embeddings (e.g. wasmtime), which then rely on the `TZ` environment variable.
+//! * `probe-env`, `probe-etc-timezone`, `probe-localtime` and `probe-config-files`: include
+//!   the built-in providers reading respectively the `TZ` environment variable,
+//!   the `/etc/timezone` (and alike) files, `/etc/localtime` and the distribution-specific
+//!   configuration files (e.g. `/etc/sysconfig/clock`). Embedded users can disable
+//!   the ones they don't need with `default-features = false` (keeping `std`).
+//! * `wasm-bindgen`: exports a `systemTz()` JavaScript function returning the IANA name
+//!   of the timezone on `wasm` targets, implies `js` (see [npm package](#npm-package)).
+//! * `update-cldr`: downloads the pinned `WindowsZones` dataset (CLDR release 44) when building
+//!   for windows targets, which requires an internet connection. The dataset integrity
+//!   is checked against its SHA-256 digest in all cases.
+//!   Downloads are cached in the user cache directory (or `SYSTEM_TZ_CACHE_DIR`).
+//! * `windows-zones`: makes the `WindowsZones` mapping (`WindowsTz`, `WindowsZone`) available
+//!   on all targets, e.g. for servers translating IANA names to Windows zones.
+//! * `runtime-cldr`: allows loading a newer `WindowsZones` dataset at runtime
+//!   (see `WindowsTz::load_from_xml()`), implies `windows-zones`.
+//! * `serde`: implements `serde::Serialize` for metadata types (e.g. `WindowsZonesVersion`).
+//! * `localized-names`: bundles CLDR metazones and their localized long names
+//!   (e.g. `Mitteleuropäische Zeit` for `Europe/Berlin`, see `LocalizedTz` and `display_name()`).
+//!   The vendored data is a subset covering the most common timezones in `en`, `de` and `fr`.
+//! * `test-util`: exposes `MockSystem` and `ProviderChain::mocked()` to test the detection
+//!   of the unix providers (with `std`) against a mocked environment, filesystem and commands.
+//!   The Windows and JavaScript probes, which query system APIs, aren't mocked.
+//! * `log` and `tracing`: emit debug events (target `system_tz`) for each probe, such as
+//!   the files and variables read, the values found and how they parsed, to find out
+//!   why a timezone was picked on a given machine.
+//! * `time`: returns the detected timezone and offset as types of the `time` ecosystem
+//!   (`system_tz_time()` with [`time-tz`](https://crates.io/crates/time-tz), and `system_utc_offset()`).
+//!   It only requires `tz-name`, so that it doesn't pull `chrono` with `default-features = false`.
+//! * `jiff`: returns the detected timezone as a `jiff::tz::TimeZone` (see `system_tz_jiff()`).
+//! * `icu`: returns the detected timezone as ICU4X types, i.e. its BCP-47 identifier
+//!   (`system_tz_bcp47()`) or a `CustomTimeZone` to format dates (`system_custom_tz()`).
+//! * `tz-rs`: returns the rules of `/etc/localtime` parsed as a `tz::TimeZone` on unix
+//!   (see `system_tz_rs()`), even when the IANA name of the timezone can't be derived.
+//! * `capi`: exports `system_tz_name()` and `system_tz_free()` to C/C++ from the `cdylib`
+//!   (`libsystem_tz.so`, `system_tz.dll`, ... built with `cargo rustc --lib --crate-type cdylib`),
+//!   as declared in the [`include/system_tz.h`](https://github.com/b4D8/system_tz/blob/main/include/system_tz.h)
+//!   header generated with [`cbindgen`](https://crates.io/crates/cbindgen) (`cbindgen --output include/system_tz.h`).
+//! * `uniffi`: exposes `systemTz()`, `systemTerritory()`, `guessTz()`, `windowsToIana()` and
+//!   `ianaToWindows()` to Kotlin and Swift with [UniFFI](https://mozilla.github.io/uniffi-rs/),
+//!   implies `windows-zones`. The bindings are generated by the `uniffi-bindgen` binary
+//!   (`uniffi-cli` feature) from the `cdylib`.
+//! * `napi`: exports a `systemTz()` function to Node.js from the `cdylib` (to rename
+//!   `system_tz.node`, built likewise), as a Node-API addon detecting the timezone natively rather than with `Intl`.
+//! * `exec-probes`: enables last-resort probes spawning external commands:
+//!   - `systemctl --user show-environment` (Linux), for `TZ` set in the systemd user session;
+//!   - `svcprop -p timezone/localtime svc:/system/timezone:default` (Solaris/illumos), for non-global zones;
+//!   - `date +"%Z %z"`, which is **heuristic** as abbreviations are ambiguous.
+//! * `hardened`: ignores the environment, which the caller controls, for setuid helpers and
+//!   security-sensitive daemons: the `SYSTEM_TZ`, `TZ` and `TZDIR` variables aren't read and
+//!   external commands aren't run (see `SystemTzOptions::hardened()` to do so at runtime).
 //!
 //! ## Command-line interface
 //!
-//! The crate provides a very basic binary which will print the system timezone on invokation.
+//! The crate provides a `tz` binary which prints the system timezone on invocation,
+//! along with subcommands to inspect, convert and validate timezones (see `tz --help`).
 //!
 //! ### Installation
 //!
@@ -55,7 +153,7 @@
 //! from a terminal:
 //!
 //! ```bash
-//! $ cargo install system_tz
+//! $ cargo install system_tz --features cli
 //! ```
 //!
 //! ### Usage
@@ -67,6 +165,144 @@
 //! Europe/Paris
 //! ```
 //!
+//! `--json` prints it along with detection metadata, for scripts and monitoring agents:
+//!
+//! ```bash
+//! $ tz --json
+//! {"tz":"Europe/Paris","source":"etc_localtime","source_name":"/etc/localtime","confidence":"configured","offset":"+01:00","abbreviation":"CET","dst":false,"next_transition":"2024-03-31T03:00:00+02:00"}
+//! ```
+//!
+//! `--watch` keeps running and prints a new line whenever the timezone changes
+//! (along with `--json`, a JSON object per line), e.g. to debug DST or locale changes
+//! (on macOS and the BSDs, changes are polled every minute):
+//!
+//! ```bash
+//! $ tz --watch
+//! Europe/Paris
+//! America/New_York
+//! ```
+//!
+//! `tz offset` and `tz abbr` print the UTC offset and the abbreviation of the timezone,
+//! now or `--at` a given date and time
+//! (local to the timezone, or with an offset as in RFC 3339):
+//!
+//! ```bash
+//! $ tz offset
+//! +01:00
+//! $ tz offset --at "2024-07-01 12:00"
+//! +02:00
+//! $ tz abbr
+//! CET
+//! ```
+//!
+//! `tz now` prints the current date and time in the timezone, as RFC 3339 by default,
+//! or `--format` as `rfc2822` or with strftime specifiers:
+//!
+//! ```bash
+//! $ tz now
+//! 2024-01-15T09:30:00+01:00
+//! $ tz now --format rfc2822
+//! Mon, 15 Jan 2024 09:30:00 +0100
+//! $ tz now --format "%H:%M %Z"
+//! 09:30 CET
+//! ```
+//!
+//! `tz convert` converts a date and time from a timezone to another one (by default, the
+//! timezone of the operating system), warning about times skipped or repeated by DST:
+//!
+//! ```bash
+//! $ tz convert "2024-03-10 09:00" America/New_York
+//! 2024-03-10 14:00:00 CET
+//! $ tz convert "2024-03-10 02:30" America/New_York Europe/Paris
+//! Warning: `2024-03-10 02:30` doesn't exist in America/New_York, as it's skipped by a DST transition: using 2024-03-10T03:30:00-04:00
+//! 2024-03-10 08:30:00 CET
+//! ```
+//!
+//! `tz list` lists the current IANA timezones, optionally of a `--country`
+//! (based on CLDR territory data):
+//!
+//! ```bash
+//! $ tz list --country FR
+//! Europe/Paris
+//! ```
+//!
+//! `tz search` finds timezones by approximate name or city, including deprecated aliases,
+//! best matches first:
+//!
+//! ```bash
+//! $ tz search pari
+//! Europe/Paris
+//! America/Paramaribo
+//! ...
+//! ```
+//!
+//! `tz windows` translates a Windows zone to an IANA name, or the other way around
+//! with `--reverse`, using the CLDR `WindowsZones` mapping:
+//!
+//! ```bash
+//! $ tz windows "Romance Standard Time"
+//! Europe/Paris
+//! $ tz windows Europe/Paris --reverse
+//! Romance Standard Time
+//! ```
+//!
+//! `tz validate` checks that a name is a valid IANA timezone, e.g. in configuration scripts,
+//! and exits with an error otherwise (or if it's a deprecated alias, with `--strict`):
+//!
+//! ```bash
+//! $ tz validate US/Eastern
+//! US/Eastern: valid, alias of America/New_York
+//! $ tz validate Europe/Pariss
+//! Europe/Pariss: invalid, did you mean Europe/Paris?
+//! ```
+//!
+//! `tz posix` prints the POSIX `TZ` rule of the timezone (or of a given one) for the current
+//! year, e.g. for busybox or devices without the timezone database. The rule is derived from
+//! the transitions of the current year, so it's only guaranteed to be valid for that year
+//! (see `to_posix_tz()`):
+//!
+//! ```bash
+//! $ tz posix
+//! CET-1CEST,M3.5.0,M10.5.0/3
+//! $ tz posix America/New_York
+//! EST5EDT,M3.2.0,M11.1.0
+//! ```
+//!
+//! `tz doctor` runs every probe, prints what each of them read, and suggests fixes
+//! when the detection fails or its sources disagree:
+//!
+//! ```bash
+//! $ tz doctor
+//! system_tz 0.4.0 on linux (x86_64)
+//! Detected: Europe/Paris in 273µs
+//! - set_override(): No override set in 4µs
+//! - SYSTEM_TZ: Environment variable isn't set in 2µs
+//! - TZ: Unknown timezone `Europe/Pariss` (read "Europe/Pariss") in 19µs
+//! - /etc/timezone: Europe/Paris (read "Europe/Paris") in 23µs
+//! ...
+//! Suggestions:
+//! - `Europe/Pariss` read from TZ isn't a known timezone: fix its spelling, or update `tz` if the zone is newer than its database
+//! ```
+//!
+//! ## npm package
+//!
+//! The detection logic can be built for JavaScript with the `wasm-bindgen` feature
+//! and [`wasm-bindgen-cli`](https://rustwasm.github.io/wasm-bindgen/reference/cli.html),
+//! e.g. to publish it as an npm package. As the `cdylib` is only built on demand,
+//! this goes through `cargo rustc` rather than `wasm-pack`, which requires it in `Cargo.toml`:
+//!
+//! ```bash
+//! $ cargo rustc --lib --release --target wasm32-unknown-unknown --crate-type cdylib --features wasm-bindgen
+//! $ wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/system_tz.wasm
+//! ```
+//!
+//! ```js
+//! import init, { systemTz } from "./pkg/system_tz.js";
+//!
+//! await init();
+//! console.log(systemTz()); // "Europe/Paris"
+//! ```
+//!
 //! ## Contribute
 //!
 //! Contributions to the project are most welcome.
@@ -80,15 +316,45 @@
 //! * [tzlocal](https://github.com/regebro/tzlocal) (MIT)
 //! * [localzone](https://github.com/mitsuhiko/localzone) (Apache-2.0).
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(clippy::pedantic)]
 #![warn(clippy::nursery)]
 #![warn(clippy::cargo)]
+#![allow(clippy::multiple_crate_versions)]
 
+extern crate alloc;
+
+// `system_tz_name()` still reads the operating system without the other `std` features
+#[cfg(all(not(feature = "std"), feature = "tz-name"))]
+extern crate std;
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(all(not(feature = "std"), feature = "chrono-tz"))]
+use alloc::vec;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(feature = "chrono-tz")]
 use chrono_tz::Tz;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod test;
 
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!("system_tz");
+
+#[cfg(feature = "chrono-tz")]
+/// Emits a debug event with the `log` and `tracing` crates (with the features of the same name).
+macro_rules! debug {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "log")]
+        ::log::debug!(target: "system_tz", $($arg)+);
+        #[cfg(feature = "tracing")]
+        ::tracing::debug!(target: "system_tz", $($arg)+);
+    }};
+}
+
+#[cfg(feature = "std")]
 /// Abstract method for timezone retreival from the current operating system.
 pub trait SystemTz {
     #[must_use]
@@ -96,263 +362,6055 @@ pub trait SystemTz {
     fn system_tz() -> Option<Tz>;
 }
 
-trait AsTz {
+#[cfg(feature = "std")]
+#[must_use]
+/// Tries to get a [`Tz`] from the operating system (same as [`SystemTz::system_tz()`]).
+pub fn system_tz() -> Option<Tz> {
+    <Tz as SystemTz>::system_tz()
+}
+
+#[cfg(feature = "std")]
+#[must_use]
+/// Returns the timezone of the operating system, or [`Tz::UTC`] if it can't be detected.
+///
+/// ```
+/// let tz = system_tz::system_tz_or_utc();
+/// println!("{}", chrono::Utc::now().with_timezone(&tz));
+/// ```
+pub fn system_tz_or_utc() -> Tz {
+    system_tz().unwrap_or(Tz::UTC)
+}
+
+#[cfg(feature = "std")]
+#[must_use]
+/// Returns the IANA name of the timezone of the operating system (e.g. `Europe/Paris`).
+///
+/// ```
+/// if let Some(name) = system_tz::system_tz_name() {
+///     println!("{name}");
+/// }
+/// ```
+pub fn system_tz_name() -> Option<String> {
+    system_tz().map(|tz| tz.name().to_owned())
+}
+
+#[cfg(all(feature = "tz-name", not(feature = "std")))]
+#[must_use]
+/// Returns the IANA name of the timezone of the operating system (e.g. `Europe/Paris`),
+/// without depending on `chrono-tz` (`tz-name` feature without `std`).
+///
+/// The name is returned as configured, without checking it against the timezone database,
+/// and only the main sources are read: the `SYSTEM_TZ` and `TZ` environment variables,
+/// `/etc/timezone` and the target of `/etc/localtime` on unix,
+/// and `Windows.Globalization.Calendar` on windows.
+///
+/// ```
+/// if let Some(name) = system_tz::system_tz_name() {
+///     println!("{name}");
+/// }
+/// ```
+pub fn system_tz_name() -> Option<String> {
+    ["SYSTEM_TZ", "TZ"]
+        .iter()
+        .filter(|_| !cfg!(feature = "hardened"))
+        .filter_map(|name| std::env::var(name).ok())
+        .find_map(|value| tz_name(value.trim_start_matches(':')))
+        .or_else(platform_tz_name)
+}
+
+#[cfg(any(feature = "tz-name", feature = "std"))]
+/// Returns `value` if it looks like an IANA name (e.g. `America/Argentina/Buenos_Aires`,
+/// `Etc/GMT+3` or `EST5EDT`) rather than a path or a POSIX `TZ` string (e.g. `JST-9`).
+fn tz_name(value: &str) -> Option<String> {
+    let value = value.trim().trim_matches('"');
+    let valid = if value.contains('/') {
+        !value.starts_with('/')
+            && value
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b"/_+-".contains(&b))
+    } else {
+        !value.is_empty() && value.bytes().all(|b| b.is_ascii_alphanumeric())
+    };
+    valid.then(|| String::from(value))
+}
+
+#[cfg(all(feature = "tz-name", not(feature = "std"), target_family = "unix"))]
+/// Reads the name from `/etc/timezone` or from the target of `/etc/localtime`.
+fn platform_tz_name() -> Option<String> {
+    std::fs::read_to_string("/etc/timezone")
+        .ok()
+        .and_then(|content| content.lines().next().and_then(tz_name))
+        .or_else(|| {
+            let target = std::fs::read_link("/etc/localtime").ok()?;
+            let (_, name) = target.to_str()?.split_once("zoneinfo/")?;
+            tz_name(
+                name.trim_start_matches("posix/")
+                    .trim_start_matches("right/"),
+            )
+        })
+}
+
+#[cfg(all(feature = "tz-name", not(feature = "std"), target_family = "windows"))]
+/// Reads the name from `Windows.Globalization.Calendar`, which returns IANA names.
+fn platform_tz_name() -> Option<String> {
+    let name = ::windows::Globalization::Calendar::new()
+        .and_then(|calendar| calendar.GetTimeZone())
+        .ok()?;
+    tz_name(&name.to_string_lossy())
+}
+
+#[cfg(all(
+    feature = "tz-name",
+    not(feature = "std"),
+    not(any(target_family = "unix", target_family = "windows"))
+))]
+/// Reads no other source on this platform.
+const fn platform_tz_name() -> Option<String> {
+    None
+}
+
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Errors of this crate, on all platforms.
+pub enum Error {
+    #[error("Unknown timezone")]
+    UnknownTimezone,
+    #[error("Unknown timezone `{raw}`")]
+    Parse {
+        /// Value read by the probe (e.g. variable value or line of a file).
+        raw: String,
+    },
+    #[error("Unsupported value `{raw}`, which isn't a timezone name")]
+    Unsupported {
+        /// Value read by the probe, e.g. a path (`:/etc/localtime`)
+        /// or a POSIX `TZ` string (`CET-1CEST,M3.5.0,M10.5.0/3`).
+        raw: String,
+    },
+    #[error("Invalid CLDR `WindowsZones` data")]
+    InvalidCldrData,
+    #[cfg(feature = "std")]
+    #[error("I/O error{}: {message}", on_path(path.as_deref()))]
+    Io {
+        /// Path of the file, if any.
+        path: Option<std::path::PathBuf>,
+        /// Kind of the underlying error.
+        kind: std::io::ErrorKind,
+        /// Message of the underlying error.
+        message: String,
+    },
+    #[cfg(feature = "std")]
+    #[error("Permission denied{}", on_path(path.as_deref()))]
+    PermissionDenied {
+        /// Path of the file, if any.
+        path: Option<std::path::PathBuf>,
+    },
+    #[error("`Windows.Globalization.Calendar` failed with HRESULT {0:#010x}")]
+    Calendar(i32),
+    #[error("`GetDynamicTimeZoneInformation` failed with error code {0}")]
+    DynamicTimeZoneInformation(u32),
+    #[error("No timezone source available")]
+    NoSource,
+    #[error("Environment variable isn't set")]
+    MissingVariable,
+    #[error("No override set")]
+    NoOverride,
+    #[error("Timed out")]
+    Timeout,
+    #[error("Timezone name isn't canonical")]
+    NonCanonical,
+    #[error("No timezone detected by {} probes", .0.len())]
+    Probes(Vec<ProbeError>),
+}
+
+#[cfg(feature = "std")]
+impl Error {
     #[must_use]
-    /// Tries to cast type to [`Tz`]
-    fn as_tz(&self) -> Option<Tz>;
+    /// Returns the error reading the file at `path`, i.e. [`Error::PermissionDenied`]
+    /// or [`Error::Io`] depending on the kind of `err`.
+    pub fn io<P: Into<std::path::PathBuf>>(path: P, err: &std::io::Error) -> Self {
+        let path = Some(path.into());
+        match err.kind() {
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied { path },
+            kind => Self::Io {
+                path,
+                kind,
+                message: err.to_string(),
+            },
+        }
+    }
+
+    /// Returns the error of a `raw` value which didn't parse: [`Error::Parse`] if it looks
+    /// like a timezone name, [`Error::Unsupported`] otherwise.
+    fn unparsed(raw: &str) -> Self {
+        let raw = raw.trim();
+        // Same preprocessing as `ParseTz`, as the value may be e.g. quoted
+        if tz_name(&unquoted(raw).replace(' ', "_")).is_some() {
+            Self::Parse { raw: raw.into() }
+        } else {
+            Self::Unsupported { raw: raw.into() }
+        }
+    }
 }
 
-impl<T: AsRef<str>> AsTz for T {
-    /// Tries to parse a `Tz`.
-    fn as_tz(&self) -> Option<Tz> {
-        Tz::from_str_insensitive(self.as_ref().trim()).ok()
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied { path: None },
+            kind => Self::Io {
+                path: None,
+                kind,
+                message: err.to_string(),
+            },
+        }
     }
 }
 
-// UNIX ////////////////////////////////////////////////////////////////////////
+#[cfg(feature = "std")]
+/// Formats the optional `path` of an error message.
+fn on_path(path: Option<&std::path::Path>) -> String {
+    path.map(|path| format!(" on `{}`", path.display()))
+        .unwrap_or_default()
+}
 
-#[cfg(target_family = "unix")]
-impl<T: chrono::TimeZone> SystemTz for T {
-    fn system_tz() -> Option<Tz> {
-        use ::std::{env, fs};
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Source from which the system timezone was (or could be) detected.
+pub enum DetectionSource {
+    /// Override set with [`set_override()`].
+    Override,
+    /// `SYSTEM_TZ` environment variable, overriding the other sources.
+    EnvSystemTz,
+    /// `TZ` environment variable.
+    EnvTz,
+    /// `/etc/timezone` file (Debian, Ubuntu).
+    EtcTimezone,
+    /// `/etc/TZ` file (Buildroot, Yocto).
+    EtcTz,
+    /// `/var/db/zoneinfo` file (FreeBSD).
+    VarDbZoneinfo,
+    /// `/etc/localtime` symbolic link into the zoneinfo database.
+    LocaltimeSymlink,
+    /// `/usr/local/etc/localtime` symbolic link into the zoneinfo database.
+    UsrLocalLocaltimeSymlink,
+    /// `/etc/sysconfig/clock` file (Red Hat, SUSE).
+    SysconfigClock,
+    /// `/etc/conf.d/clock` file (Gentoo).
+    ConfdClock,
+    /// `/etc/default/init` file (Solaris).
+    DefaultInit,
+    /// `/usr/local/etc/default/init` file.
+    UsrLocalDefaultInit,
+    /// `/etc/TIMEZONE` file (HP-UX).
+    EtcTimezoneHpux,
+    /// `/etc/localtime` file copied from the zoneinfo database.
+    LocaltimeCopy,
+    /// `systemctl --user show-environment` command.
+    SystemdUserEnvironment,
+    /// `svcprop` command (Solaris, illumos).
+    SmfTimezone,
+    /// `date` command.
+    DateCommand,
+    /// Host timezone of Wine.
+    WindowsWine,
+    /// `Windows.Globalization.Calendar` API.
+    WindowsCalendar,
+    /// `GetDynamicTimeZoneInformation` API.
+    WindowsDynamicTimeZoneInformation,
+    /// `TimeZoneKeyName` registry value.
+    WindowsRegistry,
+    /// Standard name from `GetTimeZoneInformation`, matched against the registry.
+    WindowsStandardName,
+    /// JavaScript `Intl` API.
+    JsIntl,
+    /// JavaScript `process.env.TZ`.
+    JsProcessEnv,
+    /// JavaScript native bridge.
+    JsNativeBridge,
+    /// JavaScript `Date` offsets.
+    JsDateOffset,
+    /// Custom [`TzProvider`], with its name.
+    Custom(&'static str),
+}
 
-        env::var("TZ")
-            .ok()
-            .and_then(|tz| tz.as_tz())
-            .or_else(|| {
-                fs::read_to_string("/etc/timezone")
-                    .ok()
-                    .and_then(|tz| tz.as_tz())
-            })
-            .or_else(|| {
-                fs::read_to_string("/var/db/zoneinfo")
-                    .ok()
-                    .and_then(|tz| tz.as_tz())
-            })
-            .or_else(|| {
-                // References:
-                // * https://man7.org/linux/man-pages/man5/localtime.5.html
-                // * https://www.man7.org/linux/man-pages/man1/timedatectl.1.html
-                fs::read_link("/etc/localtime")
-                    .ok()
-                    .and_then(|x| x.canonicalize().ok())
-                    .and_then(|x| {
-                        x.display()
-                            .to_string()
-                            .split_once("/zoneinfo/")
-                            .and_then(|(_, tz)| tz.as_tz())
-                    })
-            })
-            .or_else(|| {
-                fs::read_link("usr/local/etc/localtime")
-                    .ok()
-                    .and_then(|x| x.canonicalize().ok())
-                    .and_then(|x| {
-                        x.display()
-                            .to_string()
-                            .split_once("/zoneinfo/")
-                            .and_then(|(_, tz)| tz.as_tz())
-                    })
-            })
-            .or_else(|| {
-                // CentOS and OpenSUSE
-                fs::read_to_string("etc/sysconfig/clock")
-                    .ok()
-                    .and_then(|info| {
-                        info.lines()
-                            .find(|line| {
-                                let line = line.trim_start();
-                                line.starts_with("ZONE") || line.starts_with("TIMEZONE")
-                            })
-                            .and_then(|line| line.split_once('=').and_then(|(_, tz)| tz.as_tz()))
-                    })
-            })
-            .or_else(|| {
-                // Gentoo
-                fs::read_to_string("/etc/conf.d/clock")
-                    .ok()
-                    .and_then(|info| {
-                        info.lines()
-                            .find(|line| line.trim_start().starts_with("TIMEZONE"))
-                            .and_then(|line| line.split_once('=').and_then(|(_, tz)| tz.as_tz()))
-                    })
-            })
-            .or_else(|| {
-                fs::read_to_string("/etc/default/init")
-                    .ok()
-                    .and_then(|info| {
-                        info.lines()
-                            .find(|line| line.trim_start().starts_with("TZ"))
-                            .and_then(|line| line.split_once('=').and_then(|(_, tz)| tz.as_tz()))
-                    })
-            })
-            .or_else(|| {
-                fs::read_to_string("usr/local/etc/default/init")
-                    .ok()
-                    .and_then(|info| {
-                        info.lines()
-                            .find(|line| line.trim_start().starts_with("TZ"))
-                            .and_then(|line| line.split_once('=').and_then(|(_, tz)| tz.as_tz()))
-                    })
-            })
+impl DetectionSource {
+    #[must_use]
+    /// Returns the name of the environment variable, file, command or API read by the source.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Override => "set_override()",
+            Self::EnvSystemTz => "SYSTEM_TZ",
+            Self::EnvTz => "TZ",
+            Self::EtcTimezone => "/etc/timezone",
+            Self::EtcTz => "/etc/TZ",
+            Self::VarDbZoneinfo => "/var/db/zoneinfo",
+            Self::LocaltimeSymlink => "/etc/localtime",
+            Self::UsrLocalLocaltimeSymlink => "/usr/local/etc/localtime",
+            Self::SysconfigClock => "/etc/sysconfig/clock",
+            Self::ConfdClock => "/etc/conf.d/clock",
+            Self::DefaultInit => "/etc/default/init",
+            Self::UsrLocalDefaultInit => "/usr/local/etc/default/init",
+            Self::EtcTimezoneHpux => "/etc/TIMEZONE",
+            Self::LocaltimeCopy => "/etc/localtime (copy)",
+            Self::SystemdUserEnvironment => "systemctl --user show-environment",
+            Self::SmfTimezone => "svcprop",
+            Self::DateCommand => "date",
+            Self::WindowsWine => "Wine host",
+            Self::WindowsCalendar => "Windows.Globalization.Calendar",
+            Self::WindowsDynamicTimeZoneInformation => "GetDynamicTimeZoneInformation",
+            Self::WindowsRegistry => "TimeZoneKeyName",
+            Self::WindowsStandardName => "GetTimeZoneInformation",
+            Self::JsIntl => "Intl",
+            Self::JsProcessEnv => "process.env.TZ",
+            Self::JsNativeBridge => "__systemTz",
+            Self::JsDateOffset => "Date",
+            Self::Custom(name) => name,
+        }
     }
-}
 
-// WINDOWS /////////////////////////////////////////////////////////////////////
+    #[must_use]
+    /// Returns a stable identifier of the source in `snake_case` (e.g. `etc_localtime`),
+    /// for machine-readable output.
+    pub const fn id(self) -> &'static str {
+        match self {
+            Self::Override => "override",
+            Self::EnvSystemTz => "env_system_tz",
+            Self::EnvTz => "env_tz",
+            Self::EtcTimezone => "etc_timezone",
+            Self::EtcTz => "etc_tz",
+            Self::VarDbZoneinfo => "var_db_zoneinfo",
+            Self::LocaltimeSymlink => "etc_localtime",
+            Self::UsrLocalLocaltimeSymlink => "usr_local_etc_localtime",
+            Self::SysconfigClock => "sysconfig_clock",
+            Self::ConfdClock => "confd_clock",
+            Self::DefaultInit => "default_init",
+            Self::UsrLocalDefaultInit => "usr_local_default_init",
+            Self::EtcTimezoneHpux => "etc_timezone_hpux",
+            Self::LocaltimeCopy => "etc_localtime_copy",
+            Self::SystemdUserEnvironment => "systemd_user_environment",
+            Self::SmfTimezone => "smf_timezone",
+            Self::DateCommand => "date_command",
+            Self::WindowsWine => "windows_wine",
+            Self::WindowsCalendar => "windows_calendar",
+            Self::WindowsDynamicTimeZoneInformation => "windows_dynamic_time_zone_information",
+            Self::WindowsRegistry => "windows_registry",
+            Self::WindowsStandardName => "windows_standard_name",
+            Self::JsIntl => "js_intl",
+            Self::JsProcessEnv => "js_process_env",
+            Self::JsNativeBridge => "js_native_bridge",
+            Self::JsDateOffset => "js_date_offset",
+            Self::Custom(name) => name,
+        }
+    }
 
-#[cfg(target_family = "windows")]
-include!(concat!(env!("OUT_DIR"), "/windows_zones.rs"));
+    #[must_use]
+    /// Returns `true` if the source overrides the detection (see [`set_override()`]),
+    /// in which case it's always tried first.
+    pub const fn is_override(self) -> bool {
+        matches!(self, Self::Override | Self::EnvSystemTz)
+    }
 
-#[cfg(target_family = "windows")]
-trait WindowsUtf16 {
     #[must_use]
-    /// Tries to cast Windows UTF-16 to valid UTF-8.
-    fn as_utf8(&self) -> Option<String>;
-}
+    /// Returns `true` if the source reads environment variables, which the caller of the
+    /// program controls, including commands inheriting them (see [`SystemTzOptions::hardened()`]).
+    pub const fn reads_env(self) -> bool {
+        self.is_exec()
+            || matches!(
+                self,
+                Self::EnvSystemTz | Self::EnvTz | Self::JsProcessEnv | Self::WindowsWine
+            )
+    }
 
-#[cfg(target_family = "windows")]
-impl WindowsUtf16 for [u16; 32] {
-    fn as_utf8(&self) -> Option<String> {
-        Some(String::from_utf16_lossy(self.split(|x| *x == 0).next()?))
+    #[must_use]
+    /// Returns `true` if the source is read by running an external command.
+    pub const fn is_exec(self) -> bool {
+        matches!(
+            self,
+            Self::SystemdUserEnvironment | Self::SmfTimezone | Self::DateCommand
+        )
+    }
+
+    #[must_use]
+    /// Returns `true` if reading the source only reads the state of the system
+    /// (e.g. environment, files, registry or APIs), without running external
+    /// or user-provided code.
+    pub const fn is_read_only(self) -> bool {
+        !self.is_exec() && !matches!(self, Self::JsNativeBridge | Self::Custom(_))
+    }
+
+    #[must_use]
+    /// Returns `true` if the timezone detected from this source is a guess
+    /// (e.g. from an abbreviation or offsets) rather than read from a configuration.
+    pub const fn is_heuristic(self) -> bool {
+        matches!(self, Self::DateCommand | Self::JsDateOffset)
+    }
+
+    #[must_use]
+    /// Returns how much the timezone detected from this source can be trusted.
+    ///
+    /// The host timezone of Wine is authoritative, as programs running under Wine follow
+    /// the unix host, while standard names matched against the registry are heuristic,
+    /// as they're localized and may be shared by several zones.
+    pub const fn confidence(self) -> Confidence {
+        match self {
+            Self::Override
+            | Self::EnvSystemTz
+            | Self::EnvTz
+            | Self::JsProcessEnv
+            | Self::WindowsWine => Confidence::Authoritative,
+            Self::WindowsStandardName => Confidence::Heuristic,
+            _ if self.is_heuristic() => Confidence::Heuristic,
+            _ => Confidence::Configured,
+        }
     }
 }
 
-#[cfg(target_family = "windows")]
-impl WindowsUtf16 for [u16; 128] {
-    fn as_utf8(&self) -> Option<String> {
-        Some(String::from_utf16_lossy(self.split(|x| *x == 0).next()?))
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// How much a detected timezone can be trusted, e.g. to decide whether to ask
+/// users to confirm it (see [`DetectionSource::confidence()`]).
+///
+/// Levels are ordered from the least to the most trustworthy.
+pub enum Confidence {
+    /// Guessed from an abbreviation or offsets (e.g. `date` output), possibly wrong.
+    Heuristic,
+    /// Read from the configuration of the system (e.g. `/etc/localtime` or the registry).
+    Configured,
+    /// Set explicitly for the program (e.g. `TZ` environment variable or [`set_override()`]).
+    Authoritative,
+}
+
+impl Confidence {
+    #[must_use]
+    /// Returns the name of the level (e.g. `configured`).
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Heuristic => "heuristic",
+            Self::Configured => "configured",
+            Self::Authoritative => "authoritative",
+        }
     }
 }
 
-#[cfg(target_family = "windows")]
-#[derive(Debug, Clone, Copy, thiserror::Error, PartialEq, Eq, PartialOrd, Ord, Hash)]
-/// Errors of this crate.
-pub enum Error {
-    #[error("Unknown timezone")]
-    UnknownTimezone,
+impl core::fmt::Display for Confidence {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
 }
 
-#[cfg(target_family = "windows")]
-struct WindowsZonesVersion {
-    pub build_date: Option<chrono::DateTime<chrono::Utc>>,
-    pub version: (&'static str, &'static str),
-    pub hash: Option<u64>,
+impl core::fmt::Display for DetectionSource {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
 }
 
-#[cfg(target_family = "windows")]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-/// Known Microsoft Windows timezone.
-pub struct WindowsTz {
-    zone: &'static str,
-    territory: Option<&'static str>,
-    iana: Vec<&'static str>,
+/// Failure of a single probe of [`try_system_tz()`] (see [`Error::Probes`]).
+pub struct ProbeError {
+    /// Source read by the probe.
+    pub probe: DetectionSource,
+    /// Cause of the failure.
+    pub cause: Error,
 }
 
-#[cfg(target_family = "windows")]
-impl WindowsTz {
+#[cfg(feature = "chrono-tz")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Outcome of a single probe in a [`DetectionReport`].
+pub struct ProbeReport {
+    /// Source read by the probe.
+    pub probe: DetectionSource,
+    /// Raw value read by the probe (e.g. variable value, file content, chain of links,
+    /// zoneinfo file matching a copy or Windows zone name).
+    pub raw: Option<String>,
+    /// Timezone detected by the probe or the cause of its failure
+    /// (`None` if it didn't run, because an earlier probe succeeded).
+    pub result: Option<Result<Tz, Error>>,
+    /// Time spent reading the probe (`None` if it didn't run,
+    /// or on wasm and without the `std` feature, which have no clock).
+    pub elapsed: Option<core::time::Duration>,
+}
+
+#[cfg(feature = "chrono-tz")]
+impl ProbeReport {
     #[must_use]
-    /// Returns a `WindowsTz` **only if it is registered in `WindowsZones` dataset**.
-    ///
-    /// If no `territory` is provided, returns the first known `WindowsTz`,
-    /// with a matching the `zone`.
-    pub fn get(zone: &str, territory: Option<&str>) -> Option<&'static Self> {
-        WINDOWS_ZONES.iter().find(|x| {
-            let zone = x.zone == zone;
-            if territory.is_some() {
-                zone && x.territory == territory
-            } else {
-                zone
-            }
-        })
+    /// Returns `true` if the probe ran.
+    pub const fn ran(&self) -> bool {
+        self.result.is_some()
     }
 
     #[must_use]
-    /// Returns the build date of the bundled `WindowsZones` dataset.
-    pub fn build_date() -> Option<chrono::DateTime<chrono::Utc>> {
-        WINDOWS_ZONES_VERSION.build_date
+    /// Returns the timezone name read by the probe but unknown to the bundled
+    /// database (see [`Error::Parse`]), e.g. a zone added in a newer release.
+    pub fn unknown_name(&self) -> Option<&str> {
+        match &self.result {
+            Some(Err(Error::Parse { raw })) => Some(raw),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+impl core::fmt::Display for ProbeReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self.result {
+            None => write!(f, "{}: skipped", self.probe)?,
+            Some(Ok(tz)) => write!(f, "{}: {tz}", self.probe)?,
+            Some(Err(err)) => write!(f, "{}: {err}", self.probe)?,
+        }
+        if let Some(raw) = &self.raw {
+            write!(f, " (read {:?})", raw.trim())?;
+        }
+        self.elapsed
+            .map_or(Ok(()), |elapsed| write!(f, " in {elapsed:?}"))
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Detailed report of the detection of the system timezone (see [`system_tz_report()`]),
+/// e.g. for support requests.
+pub struct DetectionReport {
+    /// Detected timezone, if any.
+    pub tz: Option<Tz>,
+    /// Every probe, in order of precedence.
+    pub probes: Vec<ProbeReport>,
+    /// Total time spent detecting the timezone (`None` on wasm and without the `std` feature).
+    pub elapsed: Option<core::time::Duration>,
+}
+
+#[cfg(feature = "chrono-tz")]
+impl DetectionReport {
+    /// Returns the timezone names read by the probes which ran
+    /// but unknown to the bundled database, with their source.
+    pub fn unknown_names(&self) -> impl Iterator<Item = (DetectionSource, &str)> + '_ {
+        self.probes
+            .iter()
+            .filter_map(|probe| Some((probe.probe, probe.unknown_name()?)))
     }
 
     #[must_use]
-    /// Returns the hash of the bundled `WindowsZones` dataset.
-    pub fn hash() -> Option<u64> {
-        WINDOWS_ZONES_VERSION.hash
+    /// Returns the timezone name read by the first probe which read one,
+    /// whether or not it's known to the bundled database.
+    ///
+    /// Unlike [`DetectionReport::tz`], this is e.g. `America/Ciudad_Juarez`
+    /// on a system with a newer database, for libraries which do know it.
+    pub fn raw_name(&self) -> Option<&str> {
+        self.probes.iter().find_map(|probe| match &probe.result {
+            Some(Ok(tz)) => Some(tz.name()),
+            _ => probe.unknown_name(),
+        })
     }
 
     #[must_use]
-    /// Returns the version of the bundled `WindowsZones` dataset.
-    pub fn version() -> (&'static str, &'static str) {
-        WINDOWS_ZONES_VERSION.version
+    /// Returns the confidence in the detected timezone, i.e. of the probe which detected it.
+    pub fn confidence(&self) -> Option<Confidence> {
+        self.probes
+            .iter()
+            .find(|probe| matches!(probe.result, Some(Ok(_))))
+            .map(|probe| probe.probe.confidence())
     }
 }
 
-#[cfg(target_family = "windows")]
-impl TryFrom<&WindowsTz> for Tz {
-    type Error = Error;
+#[cfg(feature = "chrono-tz")]
+impl core::fmt::Display for DetectionReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.tz {
+            Some(tz) => write!(f, "Detected: {tz}")?,
+            None => write!(f, "Detected: none")?,
+        }
+        match self.elapsed {
+            Some(elapsed) => writeln!(f, " in {elapsed:?}")?,
+            None => writeln!(f)?,
+        }
+        self.probes
+            .iter()
+            .try_for_each(|probe| writeln!(f, "- {probe}"))
+    }
+}
 
-    fn try_from(tz: &WindowsTz) -> Result<Self, Self::Error> {
-        // This should be infaillible as timezone validity is checked while building data
-        tz.iana[0].parse().map_err(|_| Error::UnknownTimezone)
+#[cfg(feature = "chrono-tz")]
+#[derive(Debug, Clone, Copy)]
+/// Measures elapsed time where a monotonic clock is available (not on wasm nor without `std`).
+struct Stopwatch {
+    #[cfg(all(feature = "std", not(target_family = "wasm")))]
+    start: std::time::Instant,
+}
+
+#[cfg(feature = "chrono-tz")]
+impl Stopwatch {
+    #[cfg(all(feature = "std", not(target_family = "wasm")))]
+    /// Starts measuring.
+    fn start() -> Self {
+        Self {
+            start: std::time::Instant::now(),
+        }
+    }
+
+    #[cfg(any(not(feature = "std"), target_family = "wasm"))]
+    /// Starts measuring, without a clock.
+    const fn start() -> Self {
+        Self {}
+    }
+
+    #[cfg(all(feature = "std", not(target_family = "wasm")))]
+    #[allow(clippy::unnecessary_wraps)]
+    /// Returns the time elapsed since the start (as an `Option`, as on targets without a clock).
+    fn elapsed(self) -> Option<core::time::Duration> {
+        Some(self.start.elapsed())
+    }
+
+    #[cfg(any(not(feature = "std"), target_family = "wasm"))]
+    #[allow(clippy::unused_self)]
+    /// Returns the time elapsed since the start, unknown without a clock.
+    const fn elapsed(self) -> Option<core::time::Duration> {
+        None
     }
 }
 
-#[cfg(target_family = "windows")]
-impl TryFrom<&Tz> for WindowsTz {
-    type Error = Error;
+#[cfg(feature = "chrono-tz")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Timezone read by one or more probes (see [`system_tz_candidates()`]).
+pub struct Candidate {
+    /// Timezone read.
+    pub tz: Tz,
+    /// Every probe which read it, in order of precedence.
+    pub sources: Vec<DetectionSource>,
+}
 
-    fn try_from(tz: &Tz) -> Result<Self, Self::Error> {
-        WINDOWS_ZONES
+#[cfg(feature = "chrono-tz")]
+impl Candidate {
+    #[must_use]
+    /// Returns `true` if the timezone was only guessed by heuristic probes
+    /// (see [`DetectionSource::is_heuristic()`]).
+    pub fn is_heuristic(&self) -> bool {
+        self.sources.iter().all(|source| source.is_heuristic())
+    }
+
+    #[must_use]
+    /// Returns the highest confidence of the probes which read the timezone.
+    pub fn confidence(&self) -> Confidence {
+        self.sources
             .iter()
-            .find(|x| x.iana.contains(&tz.name()))
-            .cloned()
-            .ok_or(Error::UnknownTimezone)
+            .map(|source| source.confidence())
+            .max()
+            .unwrap_or(Confidence::Heuristic)
     }
 }
 
-#[cfg(target_family = "windows")]
-impl<T: chrono::TimeZone> SystemTz for T {
-    fn system_tz() -> Option<Tz> {
-        use ::windows::{
-            Globalization::Calendar,
-            Win32::System::Time::{GetDynamicTimeZoneInformation, DYNAMIC_TIME_ZONE_INFORMATION},
-        };
+#[cfg(feature = "chrono-tz")]
+impl core::fmt::Display for Candidate {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} (", self.tz)?;
+        for (i, source) in self.sources.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{source}")?;
+        }
+        write!(f, ")")
+    }
+}
 
-        Calendar::new()
-            .ok()
-            .and_then(|cal| {
-                cal.GetTimeZone()
-                    .ok()
-                    .and_then(|hstring| hstring.to_string_lossy().as_tz())
-            })
-            .or_else(|| {
-                // Reference: https://learn.microsoft.com/en-us/windows/win32/api/timezoneapi/nf-timezoneapi-gettimezoneinformation
-                let mut zone_info = DYNAMIC_TIME_ZONE_INFORMATION::default();
-                if let 0..=2 = unsafe { GetDynamicTimeZoneInformation(&mut zone_info) } {
-                    zone_info.TimeZoneKeyName.as_utf8().and_then(|zone| {
-                        WindowsTz::get(&zone, None)
-                            .and_then(|windows_tz| windows_tz.try_into().ok())
-                    })
-                } else {
-                    None
-                }
-            })
+#[cfg(feature = "chrono-tz")]
+include!(concat!(env!("OUT_DIR"), "/deprecated_names.rs"));
+
+#[cfg(feature = "chrono-tz")]
+#[must_use]
+/// Maps a deprecated name of the IANA database (e.g. `Asia/Calcutta`, `US/Eastern`)
+/// to its current name (e.g. `Asia/Kolkata`, `America/New_York`),
+/// so that equivalent configurations compare equal.
+///
+/// Locations merged into another zone (e.g. `Europe/Amsterdam`) are kept as is.
+///
+/// ```
+/// assert_eq!(system_tz::normalize(chrono_tz::Asia::Calcutta), chrono_tz::Asia::Kolkata);
+/// assert_eq!(system_tz::normalize(chrono_tz::Europe::Amsterdam), chrono_tz::Europe::Amsterdam);
+/// ```
+pub fn normalize(tz: Tz) -> Tz {
+    DEPRECATED_NAMES
+        .binary_search_by_key(&tz.name(), |(name, _)| name)
+        .ok()
+        .and_then(|i| DEPRECATED_NAMES[i].1.parse().ok())
+        .unwrap_or(tz)
+}
+
+#[cfg(feature = "chrono-tz")]
+#[must_use]
+/// Searches the timezones whose name, or a deprecated alias (e.g. `Asia/Calcutta`),
+/// approximately matches `query`, best matches first, e.g. for users who half-remember a name.
+///
+/// The query is matched case-insensitively, spaces standing for underscores:
+/// exact names and cities (the last part of names) come first, then cities starting
+/// with the query, names containing it, cities containing its letters in order,
+/// and finally cities at least as long as the query within a typo per four letters of the city.
+///
+/// ```
+/// assert_eq!(system_tz::search_tz("pari").first(), Some(&chrono_tz::Europe::Paris));
+/// assert_eq!(system_tz::search_tz("calcutta"), [chrono_tz::Asia::Kolkata]);
+/// assert_eq!(system_tz::search_tz("new yrok").first(), Some(&chrono_tz::America::New_York));
+/// ```
+pub fn search_tz(query: &str) -> Vec<Tz> {
+    let query = query.trim().to_lowercase().replace(' ', "_");
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<(u8, Tz)> = chrono_tz::TZ_VARIANTS
+        .iter()
+        .filter_map(|tz| Some((search_rank(&query, tz.name())?, normalize(*tz))))
+        .collect();
+    matches.sort_unstable_by_key(|(rank, tz)| (*rank, tz.name()));
+
+    let mut found: Vec<Tz> = Vec::new();
+    for (_, tz) in matches {
+        if !found.contains(&tz) {
+            found.push(tz);
+        }
     }
+    found
 }
 
-// WASM ////////////////////////////////////////////////////////////////////////
+#[cfg(feature = "chrono-tz")]
+/// Ranks how well a lowercase `query` matches the timezone `name` (lower is better),
+/// see [`search_tz()`].
+fn search_rank(query: &str, name: &str) -> Option<u8> {
+    let name = name.to_lowercase();
+    let city = name.rsplit('/').next().unwrap_or_default();
 
-#[cfg(target_family = "wasm")]
-impl<T: chrono::TimeZone> SystemTz for T {
-    fn system_tz() -> Option<Tz> {
-        use {js_sys::Intl::DateTimeFormat, js_sys::Reflect};
-        // Reference: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/DateTimeFormat
-        let opts = DateTimeFormat::default().resolved_options();
-        Reflect::get(&opts, &"timeZoneName".into())
-            .ok()
-            .and_then(|val| val.as_string().and_then(|s| s.as_tz()))
-            .or_else(|| {
-                Reflect::get(&opts, &"timeZone".into())
-                    .ok()
-                    .and_then(|val| val.as_string().and_then(|s| s.as_tz()))
-            })
+    let mut letters = city.chars();
+    if name == query || city == query {
+        Some(0)
+    } else if city.starts_with(query) {
+        Some(1)
+    } else if name.contains(query) {
+        Some(2)
+    } else if query.chars().all(|c| letters.any(|x| x == c)) {
+        Some(3)
+    } else if query.chars().count() > 3
+        && city.chars().count() >= query.chars().count()
+        && edit_distance(query, city) <= city.chars().count() / 4
+    {
+        // A typo per 4 letters, so that short aliases (e.g. `PRC`, `ROK`) don't match anything
+        Some(4)
+    } else {
+        None
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+/// Levenshtein distance between two strings, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(x != *y);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(feature = "chrono-tz")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// How strictly timezone names read by the providers are validated.
+pub enum ParseMode {
+    /// Only accepts current IANA names spelled with their exact case and skips
+    /// heuristic probes (see [`DetectionSource::is_heuristic()`]), e.g. for servers.
+    Strict,
+    /// Accepts deprecated names (e.g. `US/Eastern`, `Asia/Calcutta`) and any case,
+    /// and runs heuristic probes, e.g. for desktop applications.
+    #[default]
+    Lenient,
+}
+
+#[cfg(feature = "chrono-tz")]
+impl ParseMode {
+    #[must_use]
+    /// Parses the timezone `name` in this mode.
+    pub fn parse(self, name: &str) -> Option<Tz> {
+        match self {
+            Self::Strict => name
+                .trim()
+                .parse()
+                .ok()
+                .filter(|tz| !Self::is_deprecated(*tz)),
+            Self::Lenient => name.parse_tz(),
+        }
     }
+
+    #[cfg(feature = "std")]
+    /// Parses the timezone name `raw` read by a provider in this mode, after trimming it
+    /// of a leading `:` and surrounding quotes (see [`ParseTz`]).
+    ///
+    /// Names only accepted in lenient mode are reported as [`Error::NonCanonical`].
+    fn read(self, raw: &str) -> Result<Tz, Error> {
+        let name = match self {
+            Self::Strict => unquoted(raw),
+            Self::Lenient => raw,
+        };
+        self.parse(name).ok_or_else(|| {
+            if Self::Lenient.parse(raw).is_some() {
+                Error::NonCanonical
+            } else {
+                Error::unparsed(raw)
+            }
+        })
+    }
+
+    /// Returns `true` if `tz` is a backward compatibility link of the IANA database.
+    fn is_deprecated(tz: Tz) -> bool {
+        DEPRECATED_NAMES
+            .binary_search_by_key(&tz.name(), |(name, _)| name)
+            .is_ok()
+    }
+
+    /// Returns `true` if heuristic probes run in this mode.
+    const fn allows(self, source: DetectionSource) -> bool {
+        matches!(self, Self::Lenient) || !source.is_heuristic()
+    }
+
+    /// Validates a reading in this mode, rejecting deprecated names in strict mode
+    /// (e.g. from providers which don't parse names, see [`TzProvider::read_in()`]).
+    fn check(self, reading: Reading) -> Reading {
+        match reading.tz {
+            Ok(tz) if matches!(self, Self::Strict) && Self::is_deprecated(tz) => Reading {
+                tz: Err(Error::NonCanonical),
+                ..reading
+            },
+            _ => reading,
+        }
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+/// Lenient parsing of timezone names, as read from configuration files and environment variables.
+///
+/// Before matching the name case-insensitively, the value is trimmed of surrounding whitespace,
+/// a leading `:` (POSIX `TZ` form) and surrounding quotes, and spaces within the name
+/// are replaced with underscores.
+///
+/// ```
+/// use system_tz::ParseTz;
+///
+/// assert_eq!(" europe/paris\n".parse_tz(), Some(chrono_tz::Europe::Paris));
+/// assert_eq!(":Asia/Tokyo".parse_tz(), Some(chrono_tz::Asia::Tokyo));
+/// assert_eq!("\"America/New York\"".parse_tz(), Some(chrono_tz::America::New_York));
+/// assert_eq!("Not/A_Zone".parse_tz(), None);
+/// ```
+pub trait ParseTz {
+    #[must_use]
+    /// Tries to parse a [`Tz`].
+    fn parse_tz(&self) -> Option<Tz>;
+}
+
+#[cfg(feature = "chrono-tz")]
+impl<T: AsRef<str>> ParseTz for T {
+    fn parse_tz(&self) -> Option<Tz> {
+        Tz::from_str_insensitive(&unquoted(self.as_ref()).replace(' ', "_")).ok()
+    }
+}
+
+#[cfg(any(feature = "chrono-tz", feature = "std"))]
+/// Trims `value` of surrounding whitespace, a leading `:` (POSIX `TZ` form) and surrounding quotes.
+fn unquoted(value: &str) -> &str {
+    let value = value.trim();
+    let value = value.strip_prefix(':').unwrap_or(value).trim();
+    ['"', '\'']
+        .into_iter()
+        .find_map(|quote| value.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(value)
+        .trim()
+}
+
+#[cfg(feature = "chrono-tz")]
+include!(concat!(env!("OUT_DIR"), "/abbreviations.rs"));
+
+#[cfg(feature = "chrono-tz")]
+/// Common timezone abbreviations with their UTC offset (in seconds)
+/// and a representative [`Tz`], listed first among the candidates of [`FromAbbreviation`].
+const PREFERRED_ABBREVIATIONS: &[(&str, i32, Tz)] = &[
+    ("UTC", 0, Tz::UTC),
+    ("GMT", 0, Tz::Europe__London),
+    ("BST", 3600, Tz::Europe__London),
+    ("IST", 3600, Tz::Europe__Dublin),
+    ("WET", 0, Tz::Europe__Lisbon),
+    ("WEST", 3600, Tz::Europe__Lisbon),
+    ("CET", 3600, Tz::Europe__Berlin),
+    ("CEST", 7200, Tz::Europe__Berlin),
+    ("EET", 7200, Tz::Europe__Athens),
+    ("EEST", 10800, Tz::Europe__Athens),
+    ("MSK", 10800, Tz::Europe__Moscow),
+    ("WAT", 3600, Tz::Africa__Lagos),
+    ("CAT", 7200, Tz::Africa__Maputo),
+    ("SAST", 7200, Tz::Africa__Johannesburg),
+    ("EAT", 10800, Tz::Africa__Nairobi),
+    ("IST", 7200, Tz::Asia__Jerusalem),
+    ("IDT", 10800, Tz::Asia__Jerusalem),
+    ("PKT", 18000, Tz::Asia__Karachi),
+    ("IST", 19800, Tz::Asia__Kolkata),
+    ("CST", 28800, Tz::Asia__Shanghai),
+    ("HKT", 28800, Tz::Asia__Hong_Kong),
+    ("AWST", 28800, Tz::Australia__Perth),
+    ("JST", 32400, Tz::Asia__Tokyo),
+    ("KST", 32400, Tz::Asia__Seoul),
+    ("ACST", 34200, Tz::Australia__Adelaide),
+    ("ACDT", 37800, Tz::Australia__Adelaide),
+    ("AEST", 36000, Tz::Australia__Sydney),
+    ("AEDT", 39600, Tz::Australia__Sydney),
+    ("NZST", 43200, Tz::Pacific__Auckland),
+    ("NZDT", 46800, Tz::Pacific__Auckland),
+    ("NST", -12600, Tz::America__St_Johns),
+    ("NDT", -9000, Tz::America__St_Johns),
+    ("AST", -14400, Tz::America__Halifax),
+    ("ADT", -10800, Tz::America__Halifax),
+    ("EST", -18000, Tz::America__New_York),
+    ("EDT", -14400, Tz::America__New_York),
+    ("CST", -21600, Tz::America__Chicago),
+    ("CDT", -18000, Tz::America__Chicago),
+    ("MST", -25200, Tz::America__Denver),
+    ("MDT", -21600, Tz::America__Denver),
+    ("PST", -28800, Tz::America__Los_Angeles),
+    ("PDT", -25200, Tz::America__Los_Angeles),
+    ("AKST", -32400, Tz::America__Anchorage),
+    ("AKDT", -28800, Tz::America__Anchorage),
+    ("HST", -36000, Tz::Pacific__Honolulu),
+];
+
+#[cfg(feature = "chrono-tz")]
+/// Resolution of timezone abbreviations (e.g. `CET`), for tools parsing `date` output or logs.
+pub trait FromAbbreviation: Sized {
+    /// Returns the timezones using `abbreviation` (case-sensitive, e.g. `CET`), optionally
+    /// at the given UTC `offset`, based on a table of the abbreviations in use in 2024.
+    ///
+    /// Abbreviations are ambiguous (e.g. `CST` or `IST`), so every candidate is returned:
+    /// the most common zone first, then the others in alphabetical order.
+    /// Deprecated and legacy POSIX-style names (e.g. `CST6CDT`) are left out.
+    ///
+    /// ```
+    /// use system_tz::FromAbbreviation;
+    ///
+    /// let offset = chrono::FixedOffset::east_opt(19800);
+    /// assert_eq!(chrono_tz::Tz::from_abbreviation("IST", offset), [chrono_tz::Asia::Kolkata]);
+    ///
+    /// let candidates = chrono_tz::Tz::from_abbreviation("CET", None);
+    /// assert_eq!(candidates.first(), Some(&chrono_tz::Europe::Berlin));
+    /// assert!(candidates.contains(&chrono_tz::Europe::Paris));
+    /// ```
+    #[must_use]
+    fn from_abbreviation(abbreviation: &str, offset: Option<chrono::FixedOffset>) -> Vec<Self>;
+}
+
+#[cfg(feature = "chrono-tz")]
+impl FromAbbreviation for Tz {
+    fn from_abbreviation(abbreviation: &str, offset: Option<chrono::FixedOffset>) -> Vec<Self> {
+        let abbreviation = abbreviation.trim();
+        let matches = |abbr: &str, secs: i32| {
+            abbr == abbreviation && offset.is_none_or(|offset| offset.local_minus_utc() == secs)
+        };
+
+        let start = TZ_ABBREVIATIONS.partition_point(|(abbr, ..)| *abbr < abbreviation);
+        let mut candidates: Vec<Self> = TZ_ABBREVIATIONS[start..]
+            .iter()
+            .take_while(|(abbr, ..)| *abbr == abbreviation)
+            .filter(|(abbr, secs, _)| matches(abbr, *secs))
+            .filter_map(|(_, _, name)| name.parse().ok())
+            .collect();
+        candidates.sort_unstable_by_key(|tz: &Self| tz.name());
+        candidates.dedup();
+
+        let mut ranked: Vec<Self> = PREFERRED_ABBREVIATIONS
+            .iter()
+            .filter(|(abbr, secs, tz)| matches(abbr, *secs) && candidates.contains(tz))
+            .map(|(_, _, tz)| *tz)
+            .collect();
+        for tz in candidates {
+            if !ranked.contains(&tz) {
+                ranked.push(tz);
+            }
+        }
+        ranked
+    }
+}
+
+// PROVIDERS ///////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "chrono-tz")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Outcome of [`TzProvider::read()`].
+pub struct Reading {
+    /// Raw value read (e.g. variable value, file content, chain of links,
+    /// zoneinfo file matching a copy or Windows zone name), if any.
+    pub raw: Option<String>,
+    /// Detected timezone or the cause of the failure.
+    pub tz: Result<Tz, Error>,
+}
+
+#[cfg(feature = "chrono-tz")]
+/// Source of the system timezone, tried in turn with others by a [`ProviderChain`].
+pub trait TzProvider {
+    /// Returns the source read by the provider.
+    fn source(&self) -> DetectionSource;
+
+    /// Reads the source and tries to detect the timezone from it.
+    fn read(&self) -> Reading;
+
+    /// Reads the source, parsing the timezone name read in `mode` (see [`ParseMode::parse()`]).
+    ///
+    /// Defaults to [`TzProvider::read()`], as custom providers usually return a [`Tz`].
+    fn read_in(&self, mode: ParseMode) -> Reading {
+        let _ = mode;
+        self.read()
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+#[derive(Default)]
+/// Ordered list of [`TzProvider`], the first one detecting a timezone winning.
+///
+/// Custom providers can be added to the built-in ones (e.g. to read a configuration service):
+///
+/// ```
+/// use system_tz::{DetectionSource, Error, ProviderChain, Reading, TzProvider};
+///
+/// struct Config;
+///
+/// impl TzProvider for Config {
+///     fn source(&self) -> DetectionSource {
+///         DetectionSource::Custom("config")
+///     }
+///
+///     fn read(&self) -> Reading {
+///         Reading { raw: None, tz: Ok(chrono_tz::Europe::Paris) }
+///     }
+/// }
+///
+/// # #[cfg(feature = "std")] {
+/// let chain = ProviderChain::system().with_first(Config);
+/// assert_eq!(chain.detect(), Ok(chrono_tz::Europe::Paris));
+/// # }
+///
+/// // Without the `std` feature (e.g. on firmware), custom providers are the only ones
+/// let chain = ProviderChain::new().with(Config);
+/// assert_eq!(chain.detect(), Ok(chrono_tz::Europe::Paris));
+/// ```
+pub struct ProviderChain {
+    providers: Vec<alloc::sync::Arc<dyn TzProvider + Send + Sync>>,
+    probe_timeout: Option<core::time::Duration>,
+    timeout: Option<core::time::Duration>,
+    mode: ParseMode,
+    normalize: bool,
+    stop_on_unknown: bool,
+}
+
+#[cfg(feature = "chrono-tz")]
+impl ProviderChain {
+    #[cfg(feature = "std")]
+    #[must_use]
+    /// Returns the built-in providers of the current platform, in order of precedence,
+    /// after the overrides (see [`set_override()`]).
+    pub fn system() -> Self {
+        let mut chain = Self::platform()
+            .with_first(EnvProvider::new("SYSTEM_TZ").with_source(DetectionSource::EnvSystemTz))
+            .with_first(OverrideProvider);
+        if cfg!(feature = "hardened") {
+            chain
+                .providers
+                .retain(|provider| !provider.source().reads_env());
+        }
+        chain
+    }
+
+    #[must_use]
+    /// Returns an empty chain.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `provider` to the chain.
+    fn push<P: TzProvider + Send + Sync + 'static>(&mut self, provider: P) {
+        self.providers.push(alloc::sync::Arc::new(provider));
+    }
+
+    #[must_use]
+    /// Appends `provider` to the chain, tried after the current ones.
+    pub fn with<P: TzProvider + Send + Sync + 'static>(mut self, provider: P) -> Self {
+        self.push(provider);
+        self
+    }
+
+    #[must_use]
+    /// Prepends `provider` to the chain, tried before the current ones.
+    pub fn with_first<P: TzProvider + Send + Sync + 'static>(mut self, provider: P) -> Self {
+        self.providers.insert(0, alloc::sync::Arc::new(provider));
+        self
+    }
+
+    #[must_use]
+    /// Gives up on a provider once it has been reading for `timeout`,
+    /// recording [`Error::Timeout`] for it and moving on to the next one.
+    ///
+    /// Each provider is then read on a background thread, which isn't joined when it times out:
+    /// the thread of a provider hanging forever leaks, once per detection.
+    ///
+    /// Timeouts are ignored on wasm, which has no threads, and without the `std` feature.
+    pub const fn probe_timeout(mut self, timeout: core::time::Duration) -> Self {
+        self.probe_timeout = Some(timeout);
+        self
+    }
+
+    #[must_use]
+    /// Maps deprecated names read by the providers to their current name
+    /// (see [`normalize()`], disabled by default).
+    pub const fn normalize(mut self, enabled: bool) -> Self {
+        self.normalize = enabled;
+        self
+    }
+
+    #[must_use]
+    /// Validates the timezones read by the providers in `mode` (lenient by default).
+    pub const fn mode(mut self, mode: ParseMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    #[must_use]
+    /// Stops at the first provider reading a timezone name unknown to the bundled
+    /// database ([`Error::Parse`]) instead of moving on to the next one (disabled by default).
+    ///
+    /// The unknown name is then available from [`DetectionReport::raw_name()`].
+    pub const fn stop_on_unknown(mut self, enabled: bool) -> Self {
+        self.stop_on_unknown = enabled;
+        self
+    }
+
+    #[must_use]
+    /// Gives up on detection once it has been running for `timeout`,
+    /// recording [`Error::Timeout`] for the provider being read and the remaining ones.
+    ///
+    /// The provider being read is left running on a background thread, as with
+    /// [`ProviderChain::probe_timeout()`].
+    ///
+    /// Timeouts are ignored on wasm, which has no threads, and without the `std` feature.
+    pub const fn timeout(mut self, timeout: core::time::Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the sources read by the providers, in order of precedence.
+    pub fn sources(&self) -> impl Iterator<Item = DetectionSource> + '_ {
+        self.providers().map(|provider| provider.source())
+    }
+
+    /// Returns the providers allowed by the mode, in order of precedence.
+    fn providers(&self) -> impl Iterator<Item = &alloc::sync::Arc<dyn TzProvider + Send + Sync>> {
+        self.providers
+            .iter()
+            .filter(|provider| self.mode.allows(provider.source()))
+    }
+
+    /// Lazily reads each provider in turn, within the configured timeouts,
+    /// up to the first unknown name if [`ProviderChain::stop_on_unknown()`] is enabled.
+    ///
+    /// Each reading comes with the time spent on it, if known.
+    fn readings(
+        &self,
+    ) -> impl Iterator<Item = (DetectionSource, Reading, Option<core::time::Duration>)> + '_ {
+        #[cfg(all(feature = "std", not(target_family = "wasm")))]
+        let deadline = self
+            .timeout
+            .map(|timeout| std::time::Instant::now() + timeout);
+        self.providers()
+            .map(move |provider| {
+                let stopwatch = Stopwatch::start();
+                #[cfg(all(feature = "std", not(target_family = "wasm")))]
+                let reading = Self::read_within(
+                    provider,
+                    self.mode,
+                    [
+                        self.probe_timeout,
+                        deadline.map(|deadline| {
+                            deadline.saturating_duration_since(std::time::Instant::now())
+                        }),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .min(),
+                );
+                #[cfg(any(not(feature = "std"), target_family = "wasm"))]
+                let reading = provider.read_in(self.mode);
+                let mut reading = self.mode.check(reading);
+                if self.normalize {
+                    reading.tz = reading.tz.map(normalize);
+                }
+                debug!(
+                    "probe {}: found {:?}, parsed as {:?}",
+                    provider.source(),
+                    reading.raw,
+                    reading.tz
+                );
+                (provider.source(), reading, stopwatch.elapsed())
+            })
+            .scan(false, |stopped, (source, reading, elapsed)| {
+                if *stopped {
+                    return None;
+                }
+                *stopped = self.stop_on_unknown && matches!(reading.tz, Err(Error::Parse { .. }));
+                Some((source, reading, elapsed))
+            })
+    }
+
+    /// Reads `provider` in `mode` on a background thread, giving up after `timeout`.
+    #[cfg(all(feature = "std", not(target_family = "wasm")))]
+    fn read_within(
+        provider: &alloc::sync::Arc<dyn TzProvider + Send + Sync>,
+        mode: ParseMode,
+        timeout: Option<core::time::Duration>,
+    ) -> Reading {
+        let timed_out = || Reading {
+            raw: None,
+            tz: Err(Error::Timeout),
+        };
+        let Some(timeout) = timeout else {
+            return provider.read_in(mode);
+        };
+        if timeout.is_zero() {
+            return timed_out();
+        }
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let reader = alloc::sync::Arc::clone(provider);
+        let spawned = std::thread::Builder::new()
+            .name("system-tz-probe".into())
+            .spawn(move || {
+                let _ = sender.send(reader.read_in(mode));
+            });
+        if spawned.is_err() {
+            return provider.read_in(mode);
+        }
+        receiver
+            .recv_timeout(timeout)
+            .unwrap_or_else(|_| timed_out())
+    }
+
+    /// Tries each provider in turn.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Probes`] with the failure of each provider if none succeeds.
+    pub fn detect(&self) -> Result<Tz, Error> {
+        let mut errors = Vec::new();
+        for (probe, reading, _) in self.readings() {
+            match reading.tz {
+                Ok(tz) => return Ok(tz),
+                Err(cause) => errors.push(ProbeError { probe, cause }),
+            }
+        }
+        Err(Error::Probes(errors))
+    }
+
+    #[must_use]
+    /// Tries each provider in turn, returning the timezone along with its source.
+    pub fn detect_with_source(&self) -> Option<(Tz, DetectionSource)> {
+        self.readings()
+            .find_map(|(probe, reading, _)| reading.tz.ok().map(|tz| (tz, probe)))
+    }
+
+    #[must_use]
+    /// Reads every provider and returns the timezones they found, deduplicated and ranked:
+    /// those read by a non-heuristic probe first, then in order of precedence.
+    pub fn candidates(&self) -> Vec<Candidate> {
+        let mut candidates = Vec::<Candidate>::new();
+        for (source, reading, _) in self.readings() {
+            let Ok(tz) = reading.tz else {
+                continue;
+            };
+            match candidates.iter_mut().find(|candidate| candidate.tz == tz) {
+                Some(candidate) => candidate.sources.push(source),
+                None => candidates.push(Candidate {
+                    tz,
+                    sources: vec![source],
+                }),
+            }
+        }
+        candidates.sort_by_key(Candidate::is_heuristic);
+        candidates
+    }
+
+    #[must_use]
+    /// Tries each provider in turn and reports what each of them read,
+    /// and how long it took.
+    pub fn report(&self) -> DetectionReport {
+        self.report_with(false)
+    }
+
+    #[must_use]
+    /// Reads every provider, even after one succeeded, and reports what each of them read
+    /// (see [`ProviderChain::report()`]), e.g. to diagnose sources which disagree.
+    pub fn report_all(&self) -> DetectionReport {
+        self.report_with(true)
+    }
+
+    /// Reports the readings of the providers, up to the first success unless `all`.
+    fn report_with(&self, all: bool) -> DetectionReport {
+        let stopwatch = Stopwatch::start();
+        let mut tz = None;
+        let mut readings = self.readings();
+        let probes = self
+            .sources()
+            .map(|probe| {
+                let reading = if tz.is_some() && !all {
+                    None
+                } else {
+                    readings.next()
+                };
+                let Some((_, Reading { raw, tz: result }, elapsed)) = reading else {
+                    return ProbeReport {
+                        probe,
+                        raw: None,
+                        result: None,
+                        elapsed: None,
+                    };
+                };
+
+                tz = tz.or_else(|| result.as_ref().ok().copied());
+                ProbeReport {
+                    probe,
+                    raw,
+                    result: Some(result),
+                    elapsed,
+                }
+            })
+            .collect();
+
+        DetectionReport {
+            tz,
+            probes,
+            elapsed: stopwatch.elapsed(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Options of [`system_tz_with()`], selecting and ordering the built-in providers.
+///
+/// ```
+/// use system_tz::{DetectionSource, SystemTzOptions};
+///
+/// let options = SystemTzOptions::new()
+///     .env(false)
+///     .read_only(true)
+///     .order([DetectionSource::LocaltimeSymlink]);
+/// let tz = system_tz::system_tz_with(&options);
+/// ```
+#[allow(clippy::struct_excessive_bools)]
+pub struct SystemTzOptions {
+    env: bool,
+    exec: bool,
+    read_only: bool,
+    order: Vec<DetectionSource>,
+    probe_timeout: Option<core::time::Duration>,
+    timeout: Option<core::time::Duration>,
+    mode: ParseMode,
+    normalize: bool,
+    stop_on_unknown: bool,
+    hardened: bool,
+}
+
+#[cfg(feature = "std")]
+impl Default for SystemTzOptions {
+    fn default() -> Self {
+        Self {
+            env: true,
+            exec: true,
+            read_only: false,
+            order: Vec::new(),
+            probe_timeout: None,
+            timeout: None,
+            mode: ParseMode::Lenient,
+            normalize: false,
+            stop_on_unknown: false,
+            hardened: cfg!(feature = "hardened"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl SystemTzOptions {
+    #[must_use]
+    /// Returns the default options, with all the built-in providers in their usual order.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    /// Enables or disables the `TZ` environment variable (enabled by default).
+    pub const fn env(mut self, enabled: bool) -> Self {
+        self.env = enabled;
+        self
+    }
+
+    #[must_use]
+    /// Enables or disables the probes running external commands (enabled by default,
+    /// only available with the `exec-probes` feature).
+    pub const fn exec(mut self, enabled: bool) -> Self {
+        self.exec = enabled;
+        self
+    }
+
+    #[must_use]
+    /// Ignores the environment, which the caller controls, e.g. in setuid helpers
+    /// (disabled by default, always enabled with the `hardened` feature).
+    ///
+    /// Skips the sources reading environment variables (see [`DetectionSource::reads_env()`])
+    /// and ignores `TZDIR`, only consulting files and APIs owned by the system.
+    pub const fn hardened(mut self, enabled: bool) -> Self {
+        self.hardened = enabled || cfg!(feature = "hardened");
+        self
+    }
+
+    #[must_use]
+    /// Restricts detection to read-only sources (see [`DetectionSource::is_read_only()`]).
+    pub const fn read_only(mut self, enabled: bool) -> Self {
+        self.read_only = enabled;
+        self
+    }
+
+    #[must_use]
+    /// Tries the providers reading `sources` first, in this order,
+    /// followed by the others in their usual order.
+    pub fn order<I: IntoIterator<Item = DetectionSource>>(mut self, sources: I) -> Self {
+        self.order = sources.into_iter().collect();
+        self
+    }
+
+    #[must_use]
+    /// Maps deprecated names to their current name (see [`normalize()`], disabled by default).
+    pub const fn normalize(mut self, enabled: bool) -> Self {
+        self.normalize = enabled;
+        self
+    }
+
+    #[must_use]
+    /// Validates the detected timezone in `mode` (see [`ParseMode`], lenient by default).
+    pub const fn mode(mut self, mode: ParseMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    #[must_use]
+    /// Stops at the first unknown timezone name (see [`ProviderChain::stop_on_unknown()`]).
+    pub const fn stop_on_unknown(mut self, enabled: bool) -> Self {
+        self.stop_on_unknown = enabled;
+        self
+    }
+
+    #[must_use]
+    /// Gives up on a provider after `timeout` (see [`ProviderChain::probe_timeout()`]).
+    pub const fn probe_timeout(mut self, timeout: core::time::Duration) -> Self {
+        self.probe_timeout = Some(timeout);
+        self
+    }
+
+    #[must_use]
+    /// Gives up on detection after `timeout` (see [`ProviderChain::timeout()`]).
+    pub const fn timeout(mut self, timeout: core::time::Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    #[must_use]
+    /// Returns `true` if the provider reading `source` is enabled.
+    pub const fn is_enabled(&self, source: DetectionSource) -> bool {
+        (self.env || !matches!(source, DetectionSource::EnvTz))
+            && (self.exec || !source.is_exec())
+            && (!self.read_only || source.is_read_only())
+            && (!self.hardened || !source.reads_env())
+            && self.mode.allows(source)
+    }
+
+    #[must_use]
+    /// Returns the built-in providers selected and ordered by these options.
+    pub fn chain(&self) -> ProviderChain {
+        #[cfg(target_family = "unix")]
+        let mut chain = if self.hardened {
+            ProviderChain::unix(&Host::Hardened).with_first(OverrideProvider)
+        } else {
+            ProviderChain::system()
+        };
+        #[cfg(not(target_family = "unix"))]
+        let mut chain = ProviderChain::system();
+        chain
+            .providers
+            .retain(|provider| self.is_enabled(provider.source()));
+        chain.providers.sort_by_key(|provider| {
+            let source = provider.source();
+            let position = self
+                .order
+                .iter()
+                .position(|first| *first == source)
+                .unwrap_or(self.order.len());
+            (!source.is_override(), position)
+        });
+        chain.probe_timeout = self.probe_timeout;
+        chain.timeout = self.timeout;
+        chain.mode = self.mode;
+        chain.normalize = self.normalize;
+        chain.stop_on_unknown = self.stop_on_unknown;
+        chain
+    }
+}
+
+#[cfg(feature = "std")]
+#[must_use]
+/// Tries to get a [`Tz`] from the operating system with the built-in providers
+/// selected and ordered by `options`.
+pub fn system_tz_with(options: &SystemTzOptions) -> Option<Tz> {
+    options.chain().detect().ok()
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Timezone of the system, as an IANA timezone or, failing that, as an offset from UTC.
+pub enum Detected {
+    /// IANA timezone.
+    Iana(Tz),
+    /// Current offset from UTC, for zones that can't be mapped to an IANA timezone.
+    Offset(chrono::FixedOffset),
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for Detected {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Iana(tz) => write!(f, "{tz}"),
+            Self::Offset(offset) => write!(f, "{offset}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[must_use]
+/// Returns the system IANA timezone (see [`try_system_tz()`]) or the current offset from UTC.
+///
+/// The offset is the fallback when no IANA timezone can be resolved, e.g. with custom
+/// Windows zones, POSIX-only `TZ` strings or a stripped zoneinfo database.
+/// It is reported by Windows on Windows, and by [`chrono::Local`] elsewhere, except with
+/// the `hardened` feature as it honours the `TZ` environment variable.
+///
+/// As a last resort, the offset is turned into the timezone of the territory of the user
+/// (see [`system_territory()`]) currently at this offset, if there is a single one
+/// (see [`guess_tz()`]), e.g. `Asia/Karachi` for the custom Windows zone `UTC+05` in `PK`.
+pub fn system_tz_or_offset() -> Option<Detected> {
+    try_system_tz().ok().map(Detected::Iana).or_else(|| {
+        let offset = system_offset()?;
+        Some(
+            system_territory()
+                .and_then(|territory| guess_single_tz(offset, &territory))
+                .map_or(Detected::Offset(offset), Detected::Iana),
+        )
+    })
+}
+
+#[cfg(feature = "std")]
+/// Returns the timezone of `territory` currently at `offset` from UTC, if there is a single one.
+fn guess_single_tz(offset: chrono::FixedOffset, territory: &str) -> Option<Tz> {
+    match guess_tz(offset, Some(territory))[..] {
+        [tz] => Some(tz),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "std")]
+/// Returns the current offset from UTC of the system.
+fn system_offset() -> Option<chrono::FixedOffset> {
+    #[cfg(target_family = "windows")]
+    {
+        WindowsSystemTzInfo::get().and_then(|info| info.offset())
+    }
+    #[cfg(not(target_family = "windows"))]
+    {
+        // `chrono::Local` honours `TZ`, which the caller controls in hardened builds
+        (!cfg!(feature = "hardened")).then(|| *chrono::Local::now().offset())
+    }
+}
+
+#[cfg(feature = "time")]
+#[must_use]
+/// Returns the current offset from UTC of the system as a [`time::UtcOffset`].
+///
+/// It is the offset of [`system_tz_time()`], falling back with the `std` feature
+/// to the one reported by the system (see `system_tz_or_offset()`).
+///
+/// Unlike `time::UtcOffset::current_local_offset()`, it doesn't fail on unix
+/// when several threads are running.
+///
+/// ```
+/// if let Some(offset) = system_tz::system_utc_offset() {
+///     println!("{}", time::OffsetDateTime::now_utc().to_offset(offset));
+/// }
+/// ```
+pub fn system_utc_offset() -> Option<time::UtcOffset> {
+    use time_tz::{Offset, TimeZone};
+
+    let offset =
+        system_tz_time().map(|tz| tz.get_offset_utc(&time::OffsetDateTime::now_utc()).to_utc());
+
+    #[cfg(feature = "std")]
+    let offset = offset
+        .or_else(|| time::UtcOffset::from_whole_seconds(system_offset()?.local_minus_utc()).ok());
+
+    offset
+}
+
+#[cfg(feature = "time")]
+#[must_use]
+/// Tries to get the timezone of the operating system as a [`time_tz::Tz`],
+/// e.g. to convert dates of the `time` crate with [`time_tz::OffsetDateTimeExt`].
+///
+/// It is looked up by name (see [`system_tz_name()`]), so it doesn't need `chrono-tz`.
+///
+/// ```
+/// use time_tz::{OffsetDateTimeExt, TimeZone};
+///
+/// if let Some(tz) = system_tz::system_tz_time() {
+///     println!("{}: {}", tz.name(), time::OffsetDateTime::now_utc().to_timezone(tz));
+/// }
+/// ```
+///
+/// Like the other detection functions, it follows `SYSTEM_TZ` (except with the `hardened`
+/// feature), e.g. to pin the timezone in tests:
+///
+/// ```
+/// # #[cfg(not(feature = "hardened"))]
+/// # {
+/// use time_tz::TimeZone;
+///
+/// std::env::set_var("SYSTEM_TZ", "Asia/Tokyo");
+/// assert_eq!(system_tz::system_tz_time().map(TimeZone::name), Some("Asia/Tokyo"));
+/// assert_eq!(system_tz::system_utc_offset(), time::UtcOffset::from_hms(9, 0, 0).ok());
+/// # }
+/// ```
+pub fn system_tz_time() -> Option<&'static time_tz::Tz> {
+    system_tz_name().and_then(|name| time_tz::timezones::get_by_name(&name))
+}
+
+#[cfg(feature = "jiff")]
+#[must_use]
+/// Tries to get the timezone of the operating system as a [`jiff::tz::TimeZone`].
+///
+/// It is looked up by its IANA name in the timezone database of `jiff`
+/// (the system one on unix, the bundled one on windows and wasm).
+///
+/// ```
+/// if let Some(tz) = system_tz::system_tz_jiff() {
+///     println!("{}", jiff::Timestamp::now().to_zoned(tz));
+/// }
+/// ```
+pub fn system_tz_jiff() -> Option<jiff::tz::TimeZone> {
+    system_tz().and_then(|tz| jiff::tz::TimeZone::get(tz.name()).ok())
+}
+
+#[cfg(feature = "icu")]
+#[must_use]
+/// Tries to get the BCP-47 identifier of the timezone of the operating system
+/// (e.g. `frpar` for `Europe/Paris`), as used by ICU4X.
+///
+/// ```
+/// if let Some(id) = system_tz::system_tz_bcp47() {
+///     println!("{}", id.0);
+/// }
+/// ```
+pub fn system_tz_bcp47() -> Option<icu_timezone::TimeZoneBcp47Id> {
+    system_tz().and_then(bcp47)
+}
+
+#[cfg(feature = "icu")]
+/// Returns the BCP-47 identifier of `tz`.
+fn bcp47(tz: Tz) -> Option<icu_timezone::TimeZoneBcp47Id> {
+    icu_timezone::TimeZoneIdMapper::new()
+        .as_borrowed()
+        .iana_to_bcp47(tz.name())
+}
+
+#[cfg(feature = "icu")]
+#[must_use]
+/// Returns the timezone of the operating system as an ICU4X [`icu_timezone::CustomTimeZone`]
+/// (see [`system_tz_or_offset()`]), e.g. to format dates with `icu_datetime`.
+///
+/// Its current offset from UTC is always set, along with its BCP-47 identifier when
+/// an IANA timezone is detected. The metazone is left to compute for the formatted date
+/// (see [`icu_timezone::CustomTimeZone::maybe_calculate_metazone()`]).
+pub fn system_custom_tz() -> Option<icu_timezone::CustomTimeZone> {
+    use chrono::Offset;
+
+    let (id, offset) = match system_tz_or_offset()? {
+        Detected::Iana(tz) => (
+            bcp47(tz),
+            chrono::Utc::now().with_timezone(&tz).offset().fix(),
+        ),
+        Detected::Offset(offset) => (None, offset),
+    };
+    let offset = icu_timezone::GmtOffset::try_from_offset_seconds(offset.local_minus_utc()).ok()?;
+    let mut custom = icu_timezone::CustomTimeZone::new_with_offset(offset);
+    custom.time_zone_id = id;
+    Some(custom)
+}
+
+#[cfg(all(feature = "tz-rs", target_family = "unix"))]
+#[must_use]
+/// Tries to get the rules of the timezone of the operating system as a [`tz::TimeZone`],
+/// parsed from the `/etc/localtime` (or `/usr/local/etc/localtime`) file.
+///
+/// Unlike the other functions, it doesn't need to find the IANA name of the timezone,
+/// e.g. when `/etc/localtime` is a copy of a zoneinfo file missing from the database
+/// or a custom one, and applies the exact rules of the operating system.
+/// The `TZ` environment variable is ignored.
+///
+/// ```
+/// if let Some(tz) = system_tz::system_tz_rs() {
+///     println!("{:?}", tz.find_current_local_time_type());
+/// }
+/// ```
+pub fn system_tz_rs() -> Option<tz::TimeZone> {
+    ["/etc/localtime", "/usr/local/etc/localtime"]
+        .iter()
+        .find_map(|path| Host::Os.read(std::path::Path::new(path)).ok())
+        .and_then(|bytes| tz::TimeZone::from_tz_data(&bytes).ok())
+}
+
+#[cfg(feature = "std")]
+include!(concat!(env!("OUT_DIR"), "/territories.rs"));
+
+#[cfg(feature = "std")]
+#[must_use]
+/// Tries to get the territory (ISO 3166-1 alpha-2 code, e.g. `FR`) of the current user,
+/// e.g. to pass to [`guess_tz()`].
+///
+/// It is read with `GetUserDefaultGeoName` on Windows, from the locale resolved
+/// by the JavaScript `Intl` API on wasm (with the `js` feature), and otherwise from
+/// the `LC_ALL`, `LC_TIME` and `LANG` environment variables (e.g. `fr_FR.UTF-8`).
+pub fn system_territory() -> Option<String> {
+    #[cfg(target_family = "windows")]
+    {
+        windows_territory()
+    }
+    #[cfg(not(target_family = "windows"))]
+    {
+        #[cfg(all(target_family = "wasm", feature = "js"))]
+        if let Some(territory) = js_territory() {
+            return Some(territory);
+        }
+
+        // The first variable set takes precedence, as for POSIX locale categories
+        ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|x| !x.is_empty()))
+            .and_then(|locale| locale_territory(&locale))
+    }
+}
+
+#[cfg(feature = "std")]
+/// Returns whether `code` is an ISO 3166-1 alpha-2 code (e.g. `FR`).
+fn is_territory(code: &str) -> bool {
+    code.len() == 2 && code.bytes().all(|b| b.is_ascii_uppercase())
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(target_family = "windows", allow(dead_code))]
+/// Extracts the territory of a POSIX locale (e.g. `fr_FR.UTF-8@euro`)
+/// or of a BCP 47 language tag (e.g. `zh-Hant-TW`).
+fn locale_territory(locale: &str) -> Option<String> {
+    let locale = locale.split(['.', '@']).next()?;
+    locale
+        .split(['_', '-'])
+        .skip(1)
+        .map(str::to_ascii_uppercase)
+        .find(|subtag| is_territory(subtag))
+}
+
+#[cfg(feature = "std")]
+#[must_use]
+/// **Heuristic**: proposes the timezones currently at `offset` from UTC, optionally
+/// in a `territory` (ISO 3166-1 alpha-2 code, e.g. `FR`), based on CLDR territory data.
+///
+/// The main zone of each region (e.g. `America/New_York`) comes first, then the others
+/// in alphabetical order. This is meant as a last resort (as by [`system_tz_or_offset()`])
+/// or to pre-select zones in a picker, not as a reliable detection.
+///
+/// ```
+/// let offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+/// assert_eq!(system_tz::guess_tz(offset, Some("JP")), [chrono_tz::Asia::Tokyo]);
+/// assert!(system_tz::guess_tz(offset, None).contains(&chrono_tz::Asia::Seoul));
+/// ```
+pub fn guess_tz(offset: chrono::FixedOffset, territory: Option<&str>) -> Vec<Tz> {
+    use chrono::{Offset, TimeZone};
+
+    let zones = territory.map_or(TZ_TERRITORIES, territory_zones);
+
+    let now = chrono::Utc::now().naive_utc();
+    let mut guesses: Vec<(u8, Tz)> = zones
+        .iter()
+        .filter_map(|(_, name, rank)| Some((*rank, name.parse::<Tz>().ok()?)))
+        .filter(|(_, tz)| tz.offset_from_utc_datetime(&now).fix() == offset)
+        .collect();
+    guesses.sort_unstable_by_key(|(rank, tz)| (*rank, tz.name()));
+
+    let mut seen = std::collections::HashSet::new();
+    guesses
+        .into_iter()
+        .map(|(_, tz)| tz)
+        .filter(|tz| seen.insert(*tz))
+        .collect()
+}
+
+#[cfg(feature = "std")]
+#[must_use]
+/// Lists the timezones of a `territory` (ISO 3166-1 alpha-2 code, e.g. `FR`)
+/// in alphabetical order, based on CLDR territory data.
+///
+/// ```
+/// assert_eq!(system_tz::territory_tz("FR"), [chrono_tz::Europe::Paris]);
+/// assert!(system_tz::territory_tz("us").contains(&chrono_tz::America::New_York));
+/// ```
+pub fn territory_tz(territory: &str) -> Vec<Tz> {
+    // The entries of a territory are sorted by timezone
+    territory_zones(territory)
+        .iter()
+        .filter_map(|(_, name, _)| name.parse().ok())
+        .collect()
+}
+
+#[cfg(feature = "std")]
+/// Returns the entries of `TZ_TERRITORIES` for `territory`, in any case.
+fn territory_zones(territory: &str) -> &'static [(&'static str, &'static str, u8)] {
+    let territory = territory.trim().to_ascii_uppercase();
+    let start = TZ_TERRITORIES.partition_point(|(x, ..)| *x < territory.as_str());
+    let end = start + TZ_TERRITORIES[start..].partition_point(|(x, ..)| *x == territory);
+    &TZ_TERRITORIES[start..end]
+}
+
+#[cfg(feature = "chrono-tz")]
+impl core::fmt::Debug for ProviderChain {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.sources()).finish()
+    }
+}
+
+#[cfg(feature = "std")]
+/// Gets the system timezone with the built-in providers (see [`ProviderChain::system()`]).
+///
+/// # Errors
+///
+/// Returns [`Error::Probes`] with the failure of each provider if none succeeds.
+pub fn try_system_tz() -> Result<Tz, Error> {
+    ProviderChain::system().detect()
+}
+
+#[cfg(feature = "std")]
+#[must_use]
+/// Returns the system timezone along with the source it was detected from.
+pub fn system_tz_with_source() -> Option<(Tz, DetectionSource)> {
+    ProviderChain::system().detect_with_source()
+}
+
+#[cfg(feature = "std")]
+#[must_use]
+/// Returns the system timezone along with the confidence in it
+/// (see [`DetectionSource::confidence()`]), e.g. to ask users to confirm guesses.
+///
+/// ```
+/// use system_tz::Confidence;
+///
+/// if let Some((tz, Confidence::Heuristic)) = system_tz::system_tz_with_confidence() {
+///     println!("Your timezone seems to be {tz}, is that right?");
+/// }
+/// ```
+pub fn system_tz_with_confidence() -> Option<(Tz, Confidence)> {
+    system_tz_with_source().map(|(tz, source)| (tz, source.confidence()))
+}
+
+#[cfg(feature = "std")]
+#[must_use]
+/// Runs the built-in providers and reports what each of them read.
+pub fn system_tz_report() -> DetectionReport {
+    ProviderChain::system().report()
+}
+
+#[cfg(feature = "std")]
+#[must_use]
+/// Returns the name of the system timezone as read by the built-in providers,
+/// even if it's unknown to the bundled database (see [`DetectionReport::raw_name()`]).
+pub fn system_tz_raw_name() -> Option<String> {
+    system_tz_report().raw_name().map(String::from)
+}
+
+#[cfg(feature = "std")]
+#[must_use]
+/// Returns every timezone found by the built-in providers, ranked by reliability
+/// (see [`ProviderChain::candidates()`]), e.g. to let users pick one when sources disagree.
+pub fn system_tz_candidates() -> Vec<Candidate> {
+    ProviderChain::system().candidates()
+}
+
+#[cfg(feature = "std")]
+impl<T: chrono::TimeZone> SystemTz for T {
+    fn system_tz() -> Option<Tz> {
+        try_system_tz().ok()
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+/// Environment, filesystem and commands read by the built-in providers.
+enum Host {
+    /// Running system.
+    #[default]
+    Os,
+    /// Filesystem under a root (e.g. a mounted disk), without environment nor commands.
+    #[cfg(target_family = "unix")]
+    Under(alloc::sync::Arc<std::path::Path>),
+    /// Running system, without environment nor commands (see [`SystemTzOptions::hardened()`]).
+    #[cfg(target_family = "unix")]
+    Hardened,
+    /// Mocked system, for tests.
+    #[cfg(all(target_family = "unix", feature = "test-util"))]
+    Mock(alloc::sync::Arc<MockSystem>),
+}
+
+#[cfg(feature = "std")]
+impl Host {
+    /// Reads the environment variable `name`.
+    fn var(&self, name: &str) -> Result<String, std::env::VarError> {
+        let value = match self {
+            Self::Os if !cfg!(feature = "hardened") => std::env::var(name),
+            Self::Os => Err(std::env::VarError::NotPresent),
+            #[cfg(target_family = "unix")]
+            Self::Under(_) | Self::Hardened => Err(std::env::VarError::NotPresent),
+            #[cfg(all(target_family = "unix", feature = "test-util"))]
+            Self::Mock(system) => system
+                .vars
+                .get(name)
+                .cloned()
+                .ok_or(std::env::VarError::NotPresent),
+        };
+        debug!("environment variable {name}: {value:?}");
+        value
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+impl Host {
+    /// Returns the absolute `path` under `root`.
+    fn under(root: &std::path::Path, path: &std::path::Path) -> std::path::PathBuf {
+        root.join(path.strip_prefix("/").unwrap_or(path))
+    }
+
+    /// Returns the path under `root` of the file at `path`, following symbolic links
+    /// within the root (see [`symlink_chain()`]) rather than through the running system.
+    fn resolve_under(
+        &self,
+        root: &std::path::Path,
+        path: &std::path::Path,
+    ) -> std::io::Result<std::path::PathBuf> {
+        let chain = symlink_chain(self, path);
+        let target = Self::under(root, chain.last().ok_or(std::io::ErrorKind::NotFound)?);
+
+        // Dangling link or too many hops
+        if std::fs::symlink_metadata(&target)?.file_type().is_symlink() {
+            return Err(std::io::ErrorKind::NotFound.into());
+        }
+        Ok(target)
+    }
+
+    /// Reads the file at `path`, following symbolic links.
+    fn read(&self, path: &std::path::Path) -> std::io::Result<Vec<u8>> {
+        let content = match self {
+            Self::Os | Self::Hardened => std::fs::read(path),
+            Self::Under(root) => std::fs::read(self.resolve_under(root, path)?),
+            #[cfg(feature = "test-util")]
+            Self::Mock(system) => system.resolve(path).map(<[u8]>::to_vec),
+        };
+        debug!(
+            "read {}: {:?}",
+            path.display(),
+            content
+                .as_ref()
+                .map(|content| format!("{} bytes", content.len()))
+        );
+        content
+    }
+
+    /// Reads the file at `path` as UTF-8, following symbolic links.
+    fn read_to_string(&self, path: &std::path::Path) -> std::io::Result<String> {
+        String::from_utf8(self.read(path)?)
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::InvalidData))
+    }
+
+    /// Returns the length of the file at `path`, following symbolic links.
+    fn len(&self, path: &std::path::Path) -> std::io::Result<u64> {
+        match self {
+            Self::Os | Self::Hardened => std::fs::metadata(path).map(|metadata| metadata.len()),
+            Self::Under(root) => std::fs::symlink_metadata(self.resolve_under(root, path)?)
+                .map(|metadata| metadata.len()),
+            #[cfg(feature = "test-util")]
+            Self::Mock(system) => system.resolve(path).map(|content| content.len() as u64),
+        }
+    }
+
+    /// Returns `true` if `path` is a symbolic link (without following it).
+    fn is_symlink(&self, path: &std::path::Path) -> std::io::Result<bool> {
+        match self {
+            Self::Os | Self::Hardened => std::fs::symlink_metadata(path),
+            Self::Under(root) => std::fs::symlink_metadata(Self::under(root, path)),
+            #[cfg(feature = "test-util")]
+            Self::Mock(system) => {
+                return system
+                    .get(path)
+                    .map(|file| matches!(file, MockFile::Symlink(_)))
+                    .ok_or_else(|| std::io::ErrorKind::NotFound.into())
+            }
+        }
+        .map(|metadata| metadata.file_type().is_symlink())
+    }
+
+    /// Returns the target of the symbolic link at `path`.
+    ///
+    /// Absolute targets are relative to the root of the host.
+    fn read_link(&self, path: &std::path::Path) -> std::io::Result<std::path::PathBuf> {
+        let target = match self {
+            Self::Os | Self::Hardened => std::fs::read_link(path),
+            Self::Under(root) => std::fs::read_link(Self::under(root, path)),
+            #[cfg(feature = "test-util")]
+            Self::Mock(system) => match system.get(path) {
+                Some(MockFile::Symlink(target)) => Ok(target.clone()),
+                Some(MockFile::Content(_)) => Err(std::io::ErrorKind::InvalidInput.into()),
+                None => Err(std::io::ErrorKind::NotFound.into()),
+            },
+        };
+        debug!("read link {}: {target:?}", path.display());
+        target
+    }
+
+    #[cfg(feature = "exec-probes")]
+    /// Runs `command` (program and arguments) and returns its standard output if it succeeded.
+    fn run(&self, command: &[&str]) -> Option<String> {
+        let output = match self {
+            Self::Os if !cfg!(feature = "hardened") => exec::run(command),
+            Self::Os | Self::Under(_) | Self::Hardened => None,
+            #[cfg(feature = "test-util")]
+            Self::Mock(system) => system
+                .commands
+                .iter()
+                .find(|(mocked, _)| mocked.iter().eq(command))
+                .map(|(_, output)| output.clone()),
+        };
+        debug!("ran {command:?}: {output:?}");
+        output
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "unix", feature = "test-util"))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Entry of the filesystem of a [`MockSystem`].
+enum MockFile {
+    /// Regular file, with its content.
+    Content(Vec<u8>),
+    /// Symbolic link, with its target.
+    Symlink(std::path::PathBuf),
+}
+
+#[cfg(all(feature = "std", target_family = "unix", feature = "test-util"))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+/// Mocked environment, filesystem and commands read by the built-in unix providers.
+///
+/// Used with [`ProviderChain::mocked()`] to test the detection of the unix providers
+/// regardless of the configuration of the host (only available on unix hosts with
+/// the `test-util` feature: the Windows and JavaScript probes, which query system APIs,
+/// aren't mocked).
+///
+/// ```
+/// use system_tz::{MockSystem, ProviderChain};
+///
+/// let system = MockSystem::new()
+///     .symlink("/etc/localtime", "../usr/share/zoneinfo/Asia/Tokyo")
+///     .file("/usr/share/zoneinfo/Asia/Tokyo", "TZif");
+/// assert_eq!(
+///     ProviderChain::mocked(system.clone()).detect(),
+///     Ok(chrono_tz::Asia::Tokyo)
+/// );
+///
+/// let system = system.var("TZ", "Europe/Paris");
+/// assert_eq!(ProviderChain::mocked(system).detect(), Ok(chrono_tz::Europe::Paris));
+/// ```
+pub struct MockSystem {
+    vars: std::collections::BTreeMap<String, String>,
+    files: std::collections::BTreeMap<std::path::PathBuf, MockFile>,
+    commands: std::collections::BTreeMap<Vec<String>, String>,
+}
+
+#[cfg(all(feature = "std", target_family = "unix", feature = "test-util"))]
+impl MockSystem {
+    #[must_use]
+    /// Returns an empty system, without environment variables, files nor commands.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    /// Sets the environment variable `name`.
+    pub fn var<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.vars.insert(name.into(), value.into());
+        self
+    }
+
+    #[must_use]
+    /// Creates the file at the absolute `path`.
+    pub fn file<P: Into<std::path::PathBuf>, C: Into<Vec<u8>>>(
+        mut self,
+        path: P,
+        content: C,
+    ) -> Self {
+        self.files
+            .insert(path.into(), MockFile::Content(content.into()));
+        self
+    }
+
+    #[must_use]
+    /// Creates a symbolic link at the absolute `path`, pointing to `target`.
+    pub fn symlink<P: Into<std::path::PathBuf>, T: Into<std::path::PathBuf>>(
+        mut self,
+        path: P,
+        target: T,
+    ) -> Self {
+        self.files
+            .insert(path.into(), MockFile::Symlink(target.into()));
+        self
+    }
+
+    #[must_use]
+    /// Sets the standard output of `command` (program and arguments),
+    /// which otherwise fails (see the `exec-probes` feature).
+    pub fn command<O: Into<String>>(mut self, command: &[&str], output: O) -> Self {
+        self.commands.insert(
+            command.iter().map(|arg| (*arg).to_owned()).collect(),
+            output.into(),
+        );
+        self
+    }
+
+    /// Returns the entry at `path`, after resolving its `.` and `..` components.
+    fn get(&self, path: &std::path::Path) -> Option<&MockFile> {
+        use ::std::path::Component;
+
+        let mut normalized = std::path::PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    normalized.pop();
+                }
+                _ => normalized.push(component),
+            }
+        }
+        self.files.get(&normalized)
+    }
+
+    /// Returns the content of the file at `path`, following symbolic links.
+    fn resolve(&self, path: &std::path::Path) -> std::io::Result<&[u8]> {
+        let mut current = path.to_path_buf();
+        for _ in 0..=MAX_SYMLINKS {
+            match self.get(&current) {
+                Some(MockFile::Content(content)) => return Ok(content),
+                Some(MockFile::Symlink(target)) => {
+                    current = match current.parent() {
+                        Some(parent) if target.is_relative() => parent.join(target),
+                        _ => target.clone(),
+                    };
+                }
+                None => return Err(std::io::ErrorKind::NotFound.into()),
+            }
+        }
+        Err(std::io::ErrorKind::InvalidInput.into())
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Provider reading a timezone name from an environment variable,
+/// ignoring the leading `:` allowed by POSIX.
+pub struct EnvProvider {
+    variable: &'static str,
+    source: DetectionSource,
+    host: Host,
+}
+
+#[cfg(feature = "std")]
+impl EnvProvider {
+    #[must_use]
+    /// Returns a provider reading the environment variable `variable`,
+    /// reported as [`DetectionSource::EnvTz`].
+    pub const fn new(variable: &'static str) -> Self {
+        Self {
+            variable,
+            source: DetectionSource::EnvTz,
+            host: Host::Os,
+        }
+    }
+
+    #[must_use]
+    /// Reports the provider as `source`.
+    pub const fn with_source(mut self, source: DetectionSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    #[cfg(all(
+        target_family = "unix",
+        any(feature = "probe-env", feature = "test-util")
+    ))]
+    /// Reads the environment of `host`.
+    fn on(self, host: Host) -> Self {
+        Self { host, ..self }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for EnvProvider {
+    /// Returns a provider reading `TZ`.
+    fn default() -> Self {
+        Self::new("TZ")
+    }
+}
+
+#[cfg(feature = "std")]
+impl TzProvider for EnvProvider {
+    fn source(&self) -> DetectionSource {
+        self.source
+    }
+
+    fn read(&self) -> Reading {
+        self.read_in(ParseMode::default())
+    }
+
+    fn read_in(&self, mode: ParseMode) -> Reading {
+        match self.host.var(self.variable) {
+            Ok(tz) => Reading {
+                tz: mode.read(&tz),
+                raw: Some(tz),
+            },
+            Err(std::env::VarError::NotPresent) => Reading {
+                raw: None,
+                tz: Err(Error::MissingVariable),
+            },
+            Err(std::env::VarError::NotUnicode(tz)) => {
+                let raw = tz.to_string_lossy().into_owned();
+                Reading {
+                    tz: Err(Error::unparsed(&raw)),
+                    raw: Some(raw),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+/// Zone forced with [`set_override()`].
+static OVERRIDE: std::sync::RwLock<Option<Tz>> = std::sync::RwLock::new(None);
+
+#[cfg(feature = "std")]
+/// Forces the zone returned by the built-in providers (see [`ProviderChain::system()`]),
+/// taking precedence over all the probes, e.g. in tests.
+///
+/// The `SYSTEM_TZ` environment variable has the same effect, for operators.
+///
+/// ```
+/// system_tz::set_override(chrono_tz::Asia::Tokyo);
+/// assert_eq!(system_tz::system_tz(), Some(chrono_tz::Asia::Tokyo));
+///
+/// system_tz::clear_override();
+/// ```
+pub fn set_override(tz: Tz) {
+    *OVERRIDE
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(tz);
+}
+
+#[cfg(feature = "std")]
+/// Clears the zone forced with [`set_override()`].
+pub fn clear_override() {
+    *OVERRIDE
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = None;
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// Provider returning the zone forced with [`set_override()`].
+pub struct OverrideProvider;
+
+#[cfg(feature = "std")]
+impl TzProvider for OverrideProvider {
+    fn source(&self) -> DetectionSource {
+        DetectionSource::Override
+    }
+
+    fn read(&self) -> Reading {
+        let tz = *OVERRIDE
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        Reading {
+            raw: tz.map(|tz| tz.name().to_owned()),
+            tz: tz.ok_or(Error::NoOverride),
+        }
+    }
+}
+
+// UNIX ////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Provider reading a timezone name from a file, either its whole content
+/// or the value of its first `KEY=value` line starting with one of some keys.
+pub struct FileProvider {
+    source: DetectionSource,
+    path: &'static str,
+    keys: &'static [&'static str],
+    host: Host,
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+impl FileProvider {
+    #[must_use]
+    /// Returns a provider reading the whole content of the file at `path`.
+    pub const fn new(source: DetectionSource, path: &'static str) -> Self {
+        Self {
+            source,
+            path,
+            keys: &[],
+            host: Host::Os,
+        }
+    }
+
+    #[must_use]
+    /// Reads the value of the first `KEY=value` line starting with one of `keys` instead.
+    pub const fn with_keys(mut self, keys: &'static [&'static str]) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    #[must_use]
+    /// Reads the file under the filesystem `root` (e.g. a mounted disk) instead of `/`.
+    pub fn under<P: AsRef<std::path::Path>>(self, root: P) -> Self {
+        self.on(Host::Under(root.as_ref().into()))
+    }
+
+    /// Reads the file from `host`.
+    fn on(self, host: Host) -> Self {
+        Self { host, ..self }
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+impl TzProvider for FileProvider {
+    fn source(&self) -> DetectionSource {
+        self.source
+    }
+
+    fn read(&self) -> Reading {
+        self.read_in(ParseMode::default())
+    }
+
+    fn read_in(&self, mode: ParseMode) -> Reading {
+        match self.host.read_to_string(std::path::Path::new(self.path)) {
+            Ok(content) if self.keys.is_empty() => Reading {
+                tz: mode.read(&content),
+                raw: Some(content),
+            },
+            Ok(content) => Reading {
+                tz: content
+                    .lines()
+                    .find(|line| {
+                        let line = line.trim_start();
+                        self.keys.iter().any(|key| line.starts_with(key))
+                    })
+                    .and_then(|line| line.split_once('='))
+                    .map_or(Err(Error::UnknownTimezone), |(_, tz)| mode.read(tz)),
+                raw: Some(content),
+            },
+            Err(err) => Reading {
+                raw: None,
+                tz: Err(Error::io(self.path, &err)),
+            },
+        }
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Provider reading a `localtime` file, either as a symbolic link into the zoneinfo database
+/// (whose raw value is the chain of links) or as a copy of one of its files.
+pub struct LocaltimeProvider {
+    source: DetectionSource,
+    path: &'static str,
+    copy: bool,
+    host: Host,
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+impl LocaltimeProvider {
+    #[must_use]
+    /// Returns a provider reading the symbolic link at `path`.
+    pub const fn symlink(source: DetectionSource, path: &'static str) -> Self {
+        Self {
+            source,
+            path,
+            copy: false,
+            host: Host::Os,
+        }
+    }
+
+    #[must_use]
+    /// Returns a provider comparing the file at `path` with the zoneinfo database.
+    pub const fn copy(source: DetectionSource, path: &'static str) -> Self {
+        Self {
+            source,
+            path,
+            copy: true,
+            host: Host::Os,
+        }
+    }
+
+    #[must_use]
+    /// Reads the file, its links and the zoneinfo database under the filesystem `root`
+    /// (e.g. a mounted disk) instead of `/`.
+    pub fn under<P: AsRef<std::path::Path>>(self, root: P) -> Self {
+        self.on(Host::Under(root.as_ref().into()))
+    }
+
+    /// Reads the file, its links and the zoneinfo database from `host`.
+    fn on(self, host: Host) -> Self {
+        Self { host, ..self }
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+impl TzProvider for LocaltimeProvider {
+    fn source(&self) -> DetectionSource {
+        self.source
+    }
+
+    fn read(&self) -> Reading {
+        self.read_in(ParseMode::default())
+    }
+
+    fn read_in(&self, mode: ParseMode) -> Reading {
+        let host = &self.host;
+        let path = std::path::Path::new(self.path);
+        if let Err(err) = host.is_symlink(path) {
+            return Reading {
+                raw: None,
+                tz: Err(Error::io(path, &err)),
+            };
+        }
+
+        if self.copy {
+            return match zoneinfo_copy(host, path) {
+                Some((copied, tz)) => Reading {
+                    raw: Some(copied.display().to_string()),
+                    tz: Ok(tz),
+                },
+                None => Reading {
+                    raw: None,
+                    tz: Err(Error::UnknownTimezone),
+                },
+            };
+        }
+
+        // The chain is walked once, so that `raw` and `tz` agree if the link changes meanwhile
+        let chain = symlink_chain(host, path);
+        let tz = zoneinfo_link(&chain, mode).map_or_else(
+            || {
+                chain
+                    .last()
+                    .and_then(|target| zoneinfo_name(target))
+                    .map_or(Err(Error::UnknownTimezone), |name| mode.read(name))
+            },
+            Ok,
+        );
+        let raw = chain
+            .iter()
+            .map(|hop| hop.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        Reading { raw: Some(raw), tz }
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+impl ProviderChain {
+    /// Returns the built-in providers on unix: environment variable `TZ`,
+    /// configuration files and `/etc/localtime`.
+    fn platform() -> Self {
+        Self::unix(&Host::Os)
+    }
+
+    /// Returns the built-in providers on unix, reading `host`.
+    #[allow(unused_mut, unused_variables)]
+    fn unix(host: &Host) -> Self {
+        let mut chain = Self::new();
+        #[cfg(feature = "probe-env")]
+        chain.push(EnvProvider::default().on(host.clone()));
+        chain.providers.extend(Self::files(host).providers);
+
+        #[cfg(all(feature = "exec-probes", target_os = "linux"))]
+        chain.push(ExecProvider::systemd_user_environment().on(host.clone()));
+
+        #[cfg(all(
+            feature = "exec-probes",
+            any(target_os = "solaris", target_os = "illumos")
+        ))]
+        chain.push(ExecProvider::smf_timezone().on(host.clone()));
+
+        #[cfg(feature = "exec-probes")]
+        chain.push(ExecProvider::date().on(host.clone()));
+
+        chain
+    }
+
+    #[cfg(feature = "test-util")]
+    #[must_use]
+    /// Returns the built-in providers on unix, including `SYSTEM_TZ` but not
+    /// [`set_override()`], reading the mocked `system` instead of the running one.
+    pub fn mocked(system: MockSystem) -> Self {
+        let host = Host::Mock(alloc::sync::Arc::new(system));
+        Self::unix(&host).with_first(
+            EnvProvider::new("SYSTEM_TZ")
+                .with_source(DetectionSource::EnvSystemTz)
+                .on(host),
+        )
+    }
+
+    #[must_use]
+    /// Returns the built-in providers reading files on unix, under the filesystem `root`
+    /// (e.g. a chroot image or a mounted disk) instead of `/`.
+    ///
+    /// The environment and external commands, which belong to the running system,
+    /// aren't read.
+    pub fn under<P: AsRef<std::path::Path>>(root: P) -> Self {
+        Self::files(&Host::Under(root.as_ref().into()))
+    }
+
+    /// Returns the built-in providers reading configuration files and `localtime`
+    /// from `host`.
+    #[allow(unused_imports, unused_mut, unused_variables)]
+    fn files(host: &Host) -> Self {
+        use DetectionSource as S;
+
+        let mut chain = Self::new();
+        let file = |source, path| FileProvider::new(source, path).on(host.clone());
+        let symlink = |source, path| LocaltimeProvider::symlink(source, path).on(host.clone());
+        #[cfg(feature = "probe-etc-timezone")]
+        {
+            chain.push(file(S::EtcTimezone, "/etc/timezone"));
+            // Embedded (Buildroot, Yocto)
+            chain.push(file(S::EtcTz, "/etc/TZ"));
+            chain.push(file(S::VarDbZoneinfo, "/var/db/zoneinfo"));
+        }
+        // References:
+        // * https://man7.org/linux/man-pages/man5/localtime.5.html
+        // * https://www.man7.org/linux/man-pages/man1/timedatectl.1.html
+        #[cfg(feature = "probe-localtime")]
+        {
+            chain.push(symlink(S::LocaltimeSymlink, "/etc/localtime"));
+            chain.push(symlink(
+                S::UsrLocalLocaltimeSymlink,
+                "/usr/local/etc/localtime",
+            ));
+        }
+        #[cfg(feature = "probe-config-files")]
+        {
+            // CentOS and OpenSUSE
+            chain.push(
+                file(S::SysconfigClock, "/etc/sysconfig/clock").with_keys(&["ZONE", "TIMEZONE"]),
+            );
+            // Gentoo
+            chain.push(file(S::ConfdClock, "/etc/conf.d/clock").with_keys(&["TIMEZONE"]));
+            chain.push(file(S::DefaultInit, "/etc/default/init").with_keys(&["TZ"]));
+            chain.push(
+                file(S::UsrLocalDefaultInit, "/usr/local/etc/default/init").with_keys(&["TZ"]),
+            );
+            // HP-UX
+            chain.push(file(S::EtcTimezoneHpux, "/etc/TIMEZONE").with_keys(&["TZ="]));
+        }
+        // Copied rather than linked (common on embedded images)
+        #[cfg(feature = "probe-localtime")]
+        chain.push(LocaltimeProvider::copy(S::LocaltimeCopy, "/etc/localtime").on(host.clone()));
+
+        chain
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+#[must_use]
+/// Tries to get the timezone configured in the filesystem under `root`
+/// (see [`ProviderChain::under()`]), e.g. to inspect a chroot image or a mounted disk.
+pub fn system_tz_under<P: AsRef<std::path::Path>>(root: P) -> Option<Tz> {
+    ProviderChain::under(root).detect().ok()
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+/// Maximum number of symbolic links followed while resolving a path
+/// (same as Linux `SYMLOOP_MAX`).
+const MAX_SYMLINKS: usize = 40;
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+#[must_use]
+/// Resolves the chain of symbolic links starting at `path`.
+///
+/// Every hop is inspected with `lstat` so that links are never followed implicitly,
+/// relative targets are resolved against the directory of the link
+/// and resolution stops after [`MAX_SYMLINKS`] hops to guard against loops.
+///
+/// Returns the paths of the chain on `host`, starting with `path` itself
+/// (an empty chain if `path` doesn't exist).
+fn symlink_chain<P: AsRef<std::path::Path>>(host: &Host, path: P) -> Vec<std::path::PathBuf> {
+    let mut chain = Vec::new();
+    let mut current = path.as_ref().to_path_buf();
+
+    while chain.len() <= MAX_SYMLINKS {
+        let Ok(is_symlink) = host.is_symlink(&current) else {
+            break;
+        };
+
+        chain.push(current.clone());
+
+        if !is_symlink {
+            break;
+        }
+
+        let Ok(target) = host.read_link(&current) else {
+            break;
+        };
+
+        current = match current.parent() {
+            Some(parent) if target.is_relative() => parent.join(target),
+            _ => target,
+        };
+    }
+
+    chain
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+#[must_use]
+/// Tries to get a [`Tz`] from the `chain` of a symbolic link (see [`symlink_chain()`])
+/// pointing into a `zoneinfo` directory.
+///
+/// The first hop of the chain with a valid name (in `mode`) after `/zoneinfo/` wins,
+/// ignoring the `posix/` and `right/` variants of the database.
+fn zoneinfo_link(chain: &[std::path::PathBuf], mode: ParseMode) -> Option<Tz> {
+    chain
+        .iter()
+        .skip(1)
+        .find_map(|hop| mode.parse(zoneinfo_name(hop)?))
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+/// Returns the timezone name of a path into a `zoneinfo` directory,
+/// without the `posix/` and `right/` prefixes of the variants of the database.
+///
+/// The name follows the last `/zoneinfo/`, as the database may be installed
+/// under another directory of that name (e.g. `/opt/zoneinfo/share/zoneinfo`).
+fn zoneinfo_name(path: &std::path::Path) -> Option<&str> {
+    let (_, name) = path.to_str()?.rsplit_once("/zoneinfo/")?;
+    Some(
+        name.strip_prefix("posix/")
+            .or_else(|| name.strip_prefix("right/"))
+            .unwrap_or(name),
+    )
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+/// Well-known locations of the zoneinfo database.
+const ZONEINFO_DIRS: &[&str] = &[
+    "/usr/share/zoneinfo",
+    "/usr/lib/zoneinfo",
+    "/usr/share/lib/zoneinfo",
+    "/etc/zoneinfo",
+];
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+#[must_use]
+/// Returns the directories searched for the zoneinfo database on `host`.
+///
+/// The `TZDIR` environment variable, if set, takes precedence over [`ZONEINFO_DIRS`].
+fn zoneinfo_dirs(host: &Host) -> Vec<std::path::PathBuf> {
+    host.var("TZDIR")
+        .into_iter()
+        .map(std::path::PathBuf::from)
+        .chain(ZONEINFO_DIRS.iter().map(std::path::PathBuf::from))
+        .collect()
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+#[must_use]
+/// Tries to get a [`Tz`] by comparing the content of a `localtime` file
+/// copied from the zoneinfo database with the files found in [`zoneinfo_dirs()`] on `host`,
+/// along with the path of the matching file.
+///
+/// As the database contains identical files for linked zones, canonical zones are
+/// preferred over deprecated aliases (see [`normalize()`]), then in alphabetical order.
+fn zoneinfo_copy<P: AsRef<std::path::Path>>(
+    host: &Host,
+    path: P,
+) -> Option<(std::path::PathBuf, Tz)> {
+    let path = path.as_ref();
+    if host.is_symlink(path).ok()? {
+        return None;
+    }
+
+    let content = host.read(path).ok()?;
+    let len = u64::try_from(content.len()).ok()?;
+
+    let (canonical, aliases): (Vec<Tz>, Vec<Tz>) = chrono_tz::TZ_VARIANTS
+        .iter()
+        .partition(|tz| normalize(**tz) == **tz);
+    zoneinfo_dirs(host).iter().find_map(|dir| {
+        canonical.iter().chain(&aliases).find_map(|tz| {
+            let candidate = dir.join(tz.name());
+            (host
+                .len(&candidate)
+                .is_ok_and(|candidate_len| candidate_len == len)
+                && host.read(&candidate).is_ok_and(|bytes| bytes == content))
+            .then_some((candidate, *tz))
+        })
+    })
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+/// Period at which [`watch()`] checks whether it was stopped.
+const WATCH_TICK: core::time::Duration = core::time::Duration::from_secs(1);
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+/// Period at which [`watch()`] detects the timezone again without change notifications.
+const WATCH_POLL: core::time::Duration = core::time::Duration::from_secs(60);
+
+#[cfg(all(feature = "std", target_os = "macos"))]
+/// Period at which [`watch()`] detects the timezone again while it's set automatically
+/// (see [`is_automatic_timezone()`]), as it then follows the location of the device.
+const WATCH_POLL_AUTOMATIC: core::time::Duration = core::time::Duration::from_secs(5);
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+#[cfg_attr(not(target_os = "macos"), allow(clippy::missing_const_for_fn))]
+/// Returns the period at which [`watch()`] detects the timezone again without
+/// change notifications.
+fn watch_poll() -> core::time::Duration {
+    #[cfg(target_os = "macos")]
+    if is_automatic_timezone() == Some(true) {
+        return WATCH_POLL_AUTOMATIC;
+    }
+    WATCH_POLL
+}
+
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+/// Inotify instance watching `/etc` for created, replaced or modified files
+/// (e.g. `/etc/localtime` relinked by `timedatectl`).
+struct Inotify(libc::c_int);
+
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+impl Inotify {
+    /// Watches `/etc`, returning `None` if inotify is unavailable.
+    fn new() -> Option<Self> {
+        // Reference: https://man7.org/linux/man-pages/man7/inotify.7.html
+        // SAFETY: takes no pointer, returns a new file descriptor or `-1`
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return None;
+        }
+
+        let inotify = Self(fd);
+        let mask = libc::IN_CREATE
+            | libc::IN_DELETE
+            | libc::IN_MOVED_TO
+            | libc::IN_CLOSE_WRITE
+            | libc::IN_ATTRIB;
+        // SAFETY: `fd` is the inotify instance created above, and the path a valid C string
+        let watch = unsafe { libc::inotify_add_watch(fd, c"/etc".as_ptr(), mask) };
+        (watch >= 0).then_some(inotify)
+    }
+
+    /// Waits up to `timeout` for events, returning `true` (after draining them) if any.
+    fn wait(&self, timeout: core::time::Duration) -> bool {
+        let mut fds = libc::pollfd {
+            fd: self.0,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout = libc::c_int::try_from(timeout.as_millis()).unwrap_or(libc::c_int::MAX);
+        // SAFETY: `fds` is a single valid `pollfd`, as passed in `nfds`
+        if unsafe { libc::poll(::std::ptr::addr_of_mut!(fds), 1, timeout) } <= 0 {
+            return false;
+        }
+
+        let mut buffer = [0_u8; 4096];
+        // SAFETY: reads at most `buffer.len()` bytes into `buffer`, until the non-blocking
+        // descriptor has no more events
+        while unsafe { libc::read(self.0, buffer.as_mut_ptr().cast(), buffer.len()) } > 0 {}
+        true
+    }
+}
+
+#[cfg(all(feature = "std", any(target_os = "linux", target_os = "android")))]
+impl Drop for Inotify {
+    fn drop(&mut self) {
+        // SAFETY: the descriptor is owned by `self`, hence closed only once
+        unsafe { libc::close(self.0) };
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+/// Handle of a background thread watching the system timezone (see [`watch()`]),
+/// which is stopped when dropped.
+pub struct Watcher {
+    stop: alloc::sync::Arc<std::sync::atomic::AtomicBool>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+#[must_use]
+/// Watches the system timezone in a background thread,
+/// calling `callback` with the new system timezone whenever it changes.
+///
+/// On Linux, changes to `/etc` (e.g. `/etc/localtime` relinked by `timedatectl`) are notified
+/// by inotify. The timezone is also detected again every minute, which is the only way
+/// on other unix systems: on macOS and the BSDs, changes are polled, hence noticed
+/// up to a minute late. On macOS, it's detected every 5 seconds instead while it's set
+/// automatically from the location (see [`is_automatic_timezone()`]).
+///
+/// Returns `None` if the thread can't be spawned.
+pub fn watch<F>(mut callback: F) -> Option<Watcher>
+where
+    F: FnMut(Option<Tz>) + Send + 'static,
+{
+    use std::sync::{atomic::AtomicBool, atomic::Ordering, Arc};
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread = {
+        let stop = Arc::clone(&stop);
+        std::thread::Builder::new()
+            .name("system_tz-watch".into())
+            .spawn(move || {
+                #[cfg(any(target_os = "linux", target_os = "android"))]
+                let inotify = Inotify::new();
+
+                let mut current = system_tz();
+                let mut poll = watch_poll();
+                let mut polled = std::time::Instant::now();
+                while !stop.load(Ordering::Relaxed) {
+                    #[cfg(any(target_os = "linux", target_os = "android"))]
+                    let notified = inotify.as_ref().map(|inotify| inotify.wait(WATCH_TICK));
+                    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+                    let notified = None;
+
+                    if notified.is_none() {
+                        std::thread::sleep(WATCH_TICK);
+                    }
+
+                    if notified == Some(true) || polled.elapsed() >= poll {
+                        poll = watch_poll();
+                        polled = std::time::Instant::now();
+                        let tz = system_tz();
+                        if tz != current {
+                            current = tz;
+                            callback(tz);
+                        }
+                    }
+                }
+            })
+            .ok()?
+    };
+
+    Some(Watcher {
+        stop,
+        thread: Some(thread),
+    })
+}
+
+#[cfg(all(
+    feature = "std",
+    any(
+        target_family = "unix",
+        target_family = "windows",
+        all(target_family = "wasm", feature = "js")
+    )
+))]
+#[must_use]
+/// Watches the system timezone (see [`watch()`]), sending the new timezone
+/// through the returned channel whenever it changes.
+pub fn watch_channel() -> Option<(Watcher, std::sync::mpsc::Receiver<Option<Tz>>)> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let watcher = watch(move |tz| {
+        let _ = sender.send(tz);
+    })?;
+    Some((watcher, receiver))
+}
+
+#[cfg(all(
+    feature = "std",
+    any(
+        target_family = "unix",
+        target_family = "windows",
+        all(target_family = "wasm", feature = "js")
+    )
+))]
+#[derive(Clone)]
+/// Shared handle to the system timezone, kept up to date by [`watch()`],
+/// e.g. cloned into the request handlers of a web server instead of detecting it again.
+///
+/// The watcher stops when the last clone is dropped.
+///
+/// ```
+/// use system_tz::SystemTzCell;
+///
+/// let cell = SystemTzCell::new();
+/// let changes = cell.subscribe();
+/// let handler = {
+///     let cell = cell.clone();
+///     move || cell.get()
+/// };
+/// assert_eq!(handler(), system_tz::system_tz());
+/// assert!(changes.try_recv().is_err());
+/// ```
+pub struct SystemTzCell {
+    shared: alloc::sync::Arc<SystemTzShared>,
+    watcher: Option<alloc::sync::Arc<Watcher>>,
+}
+
+#[cfg(all(
+    feature = "std",
+    any(
+        target_family = "unix",
+        target_family = "windows",
+        all(target_family = "wasm", feature = "js")
+    )
+))]
+/// State of a [`SystemTzCell`], shared with its watcher.
+struct SystemTzShared {
+    tz: std::sync::RwLock<Option<Tz>>,
+    subscribers: std::sync::Mutex<Vec<std::sync::mpsc::Sender<Option<Tz>>>>,
+}
+
+#[cfg(all(
+    feature = "std",
+    any(
+        target_family = "unix",
+        target_family = "windows",
+        all(target_family = "wasm", feature = "js")
+    )
+))]
+impl SystemTzShared {
+    /// Stores the new timezone `tz` and notifies the subscribers which are still listening.
+    fn update(&self, tz: Option<Tz>) {
+        *self
+            .tz
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = tz;
+        self.subscribers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .retain(|subscriber| subscriber.send(tz).is_ok());
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    any(
+        target_family = "unix",
+        target_family = "windows",
+        all(target_family = "wasm", feature = "js")
+    )
+))]
+impl SystemTzCell {
+    #[must_use]
+    /// Detects the system timezone and starts watching it.
+    ///
+    /// If the watcher can't be started, the timezone detected at creation is kept.
+    pub fn new() -> Self {
+        let shared = alloc::sync::Arc::new(SystemTzShared {
+            tz: std::sync::RwLock::new(system_tz()),
+            subscribers: std::sync::Mutex::default(),
+        });
+        // The watcher only holds a weak reference, so that dropping the last clone stops it
+        let weak = alloc::sync::Arc::downgrade(&shared);
+        let watcher = watch(move |tz| {
+            if let Some(shared) = weak.upgrade() {
+                shared.update(tz);
+            }
+        });
+        Self {
+            shared,
+            watcher: watcher.map(alloc::sync::Arc::new),
+        }
+    }
+
+    #[must_use]
+    /// Returns the current system timezone, without detecting it again.
+    pub fn get(&self) -> Option<Tz> {
+        *self
+            .shared
+            .tz
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    #[must_use]
+    /// Returns a channel receiving the new system timezone whenever it changes.
+    pub fn subscribe(&self) -> std::sync::mpsc::Receiver<Option<Tz>> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.shared
+            .subscribers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(sender);
+        receiver
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    any(
+        target_family = "unix",
+        target_family = "windows",
+        all(target_family = "wasm", feature = "js")
+    )
+))]
+impl Default for SystemTzCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    any(
+        target_family = "unix",
+        target_family = "windows",
+        all(target_family = "wasm", feature = "js")
+    )
+))]
+impl core::fmt::Debug for SystemTzCell {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SystemTzCell")
+            .field("tz", &self.get())
+            .field("watching", &self.watcher.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "std")]
+/// Timezone of [`SystemLocal`], detected on first use.
+static SYSTEM_LOCAL: std::sync::RwLock<Option<Tz>> = std::sync::RwLock::new(None);
+
+#[cfg(all(
+    feature = "std",
+    any(
+        target_family = "unix",
+        target_family = "windows",
+        all(target_family = "wasm", feature = "js")
+    )
+))]
+/// Starts watching the timezone of [`SystemLocal`] once.
+static SYSTEM_LOCAL_WATCH: std::sync::Once = std::sync::Once::new();
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// [`chrono::TimeZone`] of the operating system, as a drop-in replacement for [`chrono::Local`]
+/// whose datetimes know their IANA timezone (e.g. displayed as `CEST` rather than `+02:00`).
+///
+/// The timezone is detected on first use (see [`system_tz_or_utc()`]), then kept
+/// up to date by [`watch()`] where available, so that conversions don't probe the system.
+/// If it can't be detected anymore after a change, it is detected again on next use.
+///
+/// ```
+/// use system_tz::SystemLocal;
+///
+/// println!("{} ({})", SystemLocal::now(), SystemLocal::tz());
+/// ```
+pub struct SystemLocal;
+
+#[cfg(feature = "std")]
+impl SystemLocal {
+    #[must_use]
+    /// Returns the current timezone of the operating system.
+    pub fn tz() -> Tz {
+        let current = *SYSTEM_LOCAL
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(tz) = current {
+            return tz;
+        }
+
+        #[cfg(any(
+            target_family = "unix",
+            target_family = "windows",
+            all(target_family = "wasm", feature = "js")
+        ))]
+        SYSTEM_LOCAL_WATCH.call_once(|| {
+            // Forgets the timezone when it can't be detected anymore after a change,
+            // so that it is detected again (or falls back to UTC) on next use
+            if let Some(watcher) = watch(|tz| {
+                *SYSTEM_LOCAL
+                    .write()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner) = tz;
+            }) {
+                // Watches for the lifetime of the program
+                core::mem::forget(watcher);
+            }
+        });
+
+        // Detects outside of the lock, so that concurrent conversions don't wait for the probes
+        let tz = system_tz_or_utc();
+        *SYSTEM_LOCAL
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get_or_insert(tz)
+    }
+
+    #[must_use]
+    /// Returns the current date and time in the timezone of the operating system.
+    pub fn now() -> chrono::DateTime<Self> {
+        chrono::Utc::now().with_timezone(&Self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl chrono::TimeZone for SystemLocal {
+    type Offset = <Tz as chrono::TimeZone>::Offset;
+
+    fn from_offset(_: &Self::Offset) -> Self {
+        Self
+    }
+
+    fn offset_from_local_date(
+        &self,
+        local: &chrono::NaiveDate,
+    ) -> chrono::LocalResult<Self::Offset> {
+        Self::tz().offset_from_local_date(local)
+    }
+
+    fn offset_from_local_datetime(
+        &self,
+        local: &chrono::NaiveDateTime,
+    ) -> chrono::LocalResult<Self::Offset> {
+        Self::tz().offset_from_local_datetime(local)
+    }
+
+    fn offset_from_utc_date(&self, utc: &chrono::NaiveDate) -> Self::Offset {
+        Self::tz().offset_from_utc_date(utc)
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &chrono::NaiveDateTime) -> Self::Offset {
+        Self::tz().offset_from_utc_datetime(utc)
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Daylight saving time and offset of a timezone, e.g. of the system one (see [`SystemTzInfo::get()`]).
+///
+/// ```
+/// use system_tz::SystemTzInfo;
+///
+/// if let Some(info) = SystemTzInfo::get() {
+///     let dst = if info.is_dst_now() { " (DST)" } else { "" };
+///     println!("{} {}{dst}", info.current_abbreviation(), info.current_offset());
+///     if let Some(transition) = info.next_transition() {
+///         println!("Next transition: {transition}");
+///     }
+/// }
+/// ```
+pub struct SystemTzInfo {
+    tz: Tz,
+}
+
+#[cfg(feature = "std")]
+impl SystemTzInfo {
+    #[must_use]
+    /// Returns the information of the system timezone, if detected.
+    pub fn get() -> Option<Self> {
+        system_tz().map(Self::new)
+    }
+
+    #[must_use]
+    /// Returns the information of `tz`.
+    pub const fn new(tz: Tz) -> Self {
+        Self { tz }
+    }
+
+    #[must_use]
+    /// Returns the timezone.
+    pub const fn tz(self) -> Tz {
+        self.tz
+    }
+
+    /// Returns the offset of the timezone at the `utc` instant.
+    fn tz_offset_at(self, utc: chrono::DateTime<chrono::Utc>) -> <Tz as chrono::TimeZone>::Offset {
+        use chrono::TimeZone;
+
+        self.tz.offset_from_utc_datetime(&utc.naive_utc())
+    }
+
+    #[must_use]
+    /// Returns whether daylight saving time is in effect at the `utc` instant.
+    pub fn is_dst_at(self, utc: chrono::DateTime<chrono::Utc>) -> bool {
+        use chrono_tz::OffsetComponents;
+
+        self.tz_offset_at(utc).dst_offset() != chrono::Duration::zero()
+    }
+
+    #[must_use]
+    /// Returns whether daylight saving time is currently in effect.
+    pub fn is_dst_now(self) -> bool {
+        self.is_dst_at(chrono::Utc::now())
+    }
+
+    #[must_use]
+    /// Returns the abbreviation in effect at the `utc` instant (e.g. `CEST`, or `+03`
+    /// for zones without one).
+    pub fn abbreviation_at(self, utc: chrono::DateTime<chrono::Utc>) -> String {
+        use chrono_tz::OffsetName;
+
+        self.tz_offset_at(utc).abbreviation().to_owned()
+    }
+
+    #[must_use]
+    /// Returns the abbreviation currently in effect (e.g. `CEST`).
+    pub fn current_abbreviation(self) -> String {
+        self.abbreviation_at(chrono::Utc::now())
+    }
+
+    #[must_use]
+    /// Returns the offset from UTC in effect at the `utc` instant.
+    pub fn offset_at(self, utc: chrono::DateTime<chrono::Utc>) -> chrono::FixedOffset {
+        use chrono::Offset;
+
+        self.tz_offset_at(utc).fix()
+    }
+
+    #[must_use]
+    /// Returns the offset from UTC currently in effect.
+    pub fn current_offset(self) -> chrono::FixedOffset {
+        self.offset_at(chrono::Utc::now())
+    }
+
+    #[must_use]
+    /// Returns the first instant after `utc` when the offset or the abbreviation changes
+    /// (e.g. when daylight saving time starts or ends), within a year.
+    pub fn next_transition_after(
+        self,
+        utc: chrono::DateTime<chrono::Utc>,
+    ) -> Option<chrono::DateTime<Tz>> {
+        use chrono::TimeZone;
+
+        let timestamp = next_transition(self.tz, utc.timestamp())?;
+        self.tz.timestamp_opt(timestamp, 0).single()
+    }
+
+    #[must_use]
+    /// Returns the next instant when the offset or the abbreviation changes, within a year.
+    pub fn next_transition(self) -> Option<chrono::DateTime<Tz>> {
+        self.next_transition_after(chrono::Utc::now())
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<Tz> for SystemTzInfo {
+    fn from(tz: Tz) -> Self {
+        Self::new(tz)
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+/// Horizon of the search for the next transition, in days.
+const TRANSITION_HORIZON: i64 = 366;
+
+#[cfg(feature = "chrono-tz")]
+/// Returns the timestamp of the first change of offset or abbreviation of `tz`
+/// after the `start` timestamp, within [`TRANSITION_HORIZON`].
+fn next_transition(tz: Tz, start: i64) -> Option<i64> {
+    use chrono::TimeZone;
+
+    const DAY: i64 = 24 * 3600;
+
+    let offset = |timestamp: i64| Some(*tz.timestamp_opt(timestamp, 0).single()?.offset());
+    let current = offset(start)?;
+    let changed = |timestamp: i64| offset(timestamp) != Some(current);
+
+    // Transitions are at least days apart: find the day, then the second
+    let mut high = (1..=TRANSITION_HORIZON)
+        .map(|days| start + days * DAY)
+        .find(|timestamp| changed(*timestamp))?;
+    let mut low = high - DAY;
+    while high - low > 1 {
+        let middle = low + (high - low) / 2;
+        if changed(middle) {
+            high = middle;
+        } else {
+            low = middle;
+        }
+    }
+    Some(high)
+}
+
+#[cfg(feature = "chrono-tz")]
+#[must_use]
+/// Returns the POSIX `TZ` string following the rules of `tz` during `year`.
+///
+/// E.g. `CET-1CEST,M3.5.0,M10.5.0/3` for `Europe/Paris`, for systems which only
+/// understand these strings (e.g. busybox or embedded devices).
+///
+/// The `M` rules are derived from the transitions of `year` alone, so the string is only
+/// guaranteed to be valid for `year`: rules which don't fall on a fixed weekday of the month
+/// at the same time every year are spelled as they happen to fall in `year`. E.g. the rule
+/// `M9.1.6/24` of `America/Santiago` (midnight after the first Saturday of September) comes
+/// out as `M9.1.0/0` in 2023 but `M9.2.0/0` in 2024, where September starts on a Sunday.
+/// The footer of the zoneinfo file gives the rule for all years after its last transition.
+///
+/// Returns `None` if the rules of the year can't be expressed as a POSIX string,
+/// e.g. with more than two transitions or a permanent change of offset.
+///
+/// ```
+/// let paris = system_tz::to_posix_tz(chrono_tz::Europe::Paris, 2023);
+/// assert_eq!(paris.as_deref(), Some("CET-1CEST,M3.5.0,M10.5.0/3"));
+/// let kolkata = system_tz::to_posix_tz(chrono_tz::Asia::Kolkata, 2023);
+/// assert_eq!(kolkata.as_deref(), Some("IST-5:30"));
+/// ```
+pub fn to_posix_tz(tz: Tz, year: i32) -> Option<String> {
+    use chrono::{Offset, TimeZone};
+    use chrono_tz::OffsetName;
+
+    let new_year = |year: i32| {
+        Some(
+            chrono::Utc
+                .with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+                .single()?
+                .timestamp(),
+        )
+    };
+    let (start, end) = (new_year(year)?, new_year(year.checked_add(1)?)?);
+    let offset = |timestamp: i64| Some(*tz.timestamp_opt(timestamp, 0).single()?.offset());
+
+    let mut transitions = Vec::new();
+    let mut cursor = start;
+    while let Some(transition) = next_transition(tz, cursor).filter(|x| *x < end) {
+        if transitions.len() == 2 {
+            return None;
+        }
+        transitions.push(transition);
+        cursor = transition;
+    }
+
+    let name = |offset: <Tz as TimeZone>::Offset| {
+        let name = offset.abbreviation();
+        if name.len() >= 3 && name.chars().all(|c| c.is_ascii_alphabetic()) {
+            String::from(name)
+        } else {
+            alloc::format!("<{name}>")
+        }
+    };
+
+    match transitions[..] {
+        [] => {
+            let offset = offset(start)?;
+            Some(alloc::format!(
+                "{}{}",
+                name(offset),
+                posix_offset(offset.fix())
+            ))
+        }
+        [first, second] => {
+            let (first_offset, second_offset) = (offset(first)?, offset(second)?);
+            let (dst, std) = match first_offset
+                .fix()
+                .local_minus_utc()
+                .cmp(&second_offset.fix().local_minus_utc())
+            {
+                core::cmp::Ordering::Greater => ((first, first_offset), (second, second_offset)),
+                core::cmp::Ordering::Less => ((second, second_offset), (first, first_offset)),
+                core::cmp::Ordering::Equal => return None,
+            };
+
+            let mut posix = alloc::format!(
+                "{}{}{}",
+                name(std.1),
+                posix_offset(std.1.fix()),
+                name(dst.1)
+            );
+            if dst.1.fix().local_minus_utc() - std.1.fix().local_minus_utc() != 3600 {
+                posix.push_str(&posix_offset(dst.1.fix()));
+            }
+            // Transitions happen at the local time in effect before them
+            posix.push_str(&posix_rule(dst.0, std.1.fix())?);
+            posix.push_str(&posix_rule(std.0, dst.1.fix())?);
+            Some(posix)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+/// Formats a duration in seconds as `hh[:mm[:ss]]`, as in POSIX `TZ` strings.
+fn posix_time(seconds: i32) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.unsigned_abs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    match (minutes, seconds) {
+        (0, 0) => alloc::format!("{sign}{hours}"),
+        (_, 0) => alloc::format!("{sign}{hours}:{minutes:02}"),
+        _ => alloc::format!("{sign}{hours}:{minutes:02}:{seconds:02}"),
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+/// Formats `offset` as in POSIX `TZ` strings, i.e. positive west of Greenwich.
+fn posix_offset(offset: chrono::FixedOffset) -> String {
+    posix_time(-offset.local_minus_utc())
+}
+
+#[cfg(feature = "chrono-tz")]
+/// Formats the transition at `timestamp` as a POSIX `TZ` rule (e.g. `,M3.5.0/3`),
+/// i.e. the `n`-th (or last) weekday of the month, at the local time in the `before` offset.
+fn posix_rule(timestamp: i64, before: chrono::FixedOffset) -> Option<String> {
+    use chrono::{Datelike, TimeZone, Timelike};
+
+    let local = before.timestamp_opt(timestamp, 0).single()?.naive_local();
+    let date = local.date();
+    let week = if date.checked_add_signed(chrono::Duration::days(7))?.month() == date.month() {
+        date.day0() / 7 + 1
+    } else {
+        5
+    };
+    let weekday = date.weekday().num_days_from_sunday();
+    let time = i32::try_from(local.num_seconds_from_midnight()).ok()?;
+    let time = if time == 7200 {
+        String::new()
+    } else {
+        alloc::format!("/{}", posix_time(time))
+    };
+    Some(alloc::format!(",M{}.{week}.{weekday}{time}", date.month()))
+}
+
+// MACOS ///////////////////////////////////////////////////////////////////////
+
+#[cfg(all(feature = "std", target_os = "macos"))]
+#[must_use]
+/// Returns whether "Set time zone automatically using your current location"
+/// is enabled in the macOS settings.
+///
+/// When enabled, the system timezone may silently change while the program runs
+/// (e.g. when travelling): [`watch()`] then detects it every few seconds rather than
+/// every minute, so that [`SystemLocal`] and [`SystemTzCell`] follow the device.
+pub fn is_automatic_timezone() -> Option<bool> {
+    plist::Value::from_file("/Library/Preferences/com.apple.timezone.auto.plist")
+        .ok()?
+        .as_dictionary()?
+        .get("Active")?
+        .as_boolean()
+}
+
+#[cfg(all(feature = "std", target_os = "macos"))]
+#[must_use]
+/// Tries to get the timezone of the city last selected in the settings,
+/// from the global preferences of the user whose home directory is `home`.
+fn macos_user_tz(home: &std::path::Path) -> Option<Tz> {
+    plist::Value::from_file(home.join("Library/Preferences/.GlobalPreferences.plist"))
+        .ok()?
+        .as_dictionary()?
+        .get("com.apple.TimeZonePref.Last_Selected_City")?
+        .as_array()?
+        .iter()
+        .filter_map(plist::Value::as_string)
+        .filter(|value| value.contains('/'))
+        .find_map(|value| value.parse_tz())
+}
+
+// USERS ///////////////////////////////////////////////////////////////////////
+
+#[cfg(all(
+    feature = "std",
+    any(target_family = "unix", target_family = "windows")
+))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// User whose timezone is detected by [`system_tz_for_user()`].
+pub enum User {
+    #[cfg(target_family = "unix")]
+    /// Numeric user id (unix only, Windows identifying users by SIDs).
+    Id(u32),
+    /// Login name (e.g. `alice`, or `DOMAIN\alice` on Windows).
+    Name(String),
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+impl From<u32> for User {
+    fn from(uid: u32) -> Self {
+        Self::Id(uid)
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    any(target_family = "unix", target_family = "windows")
+))]
+impl From<&str> for User {
+    fn from(name: &str) -> Self {
+        Self::Name(name.into())
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    any(target_family = "unix", target_family = "windows")
+))]
+impl From<String> for User {
+    fn from(name: String) -> Self {
+        Self::Name(name)
+    }
+}
+
+#[cfg(all(
+    feature = "std",
+    any(target_family = "unix", target_family = "windows")
+))]
+#[must_use]
+/// Tries to get the timezone of `user`, e.g. for daemons running as root
+/// which schedule actions on behalf of users.
+///
+/// Reads the settings of the user:
+/// * `TZ` in `~/.config/environment.d/*.conf` on unix (see `environment.d(5)`),
+/// * the city last selected in `~/Library/Preferences/.GlobalPreferences.plist` on macOS,
+/// * the `TZ` environment variable of the user in the `HKEY_USERS` registry hive on Windows,
+///   only loaded while the user is logged on.
+///
+/// Falls back to the timezone of the system, as configured in its files and APIs only
+/// (see [`SystemTzOptions::hardened()`]): the environment of the caller, e.g. a daemon
+/// started with its own `TZ`, says nothing of the user. Returns `None` if the user
+/// doesn't exist.
+///
+/// ```
+/// let tz = system_tz::system_tz_for_user("root");
+/// # #[cfg(target_family = "unix")]
+/// let tz = system_tz::system_tz_for_user(0);
+/// ```
+pub fn system_tz_for_user<U: Into<User>>(user: U) -> Option<Tz> {
+    let user = user.into();
+
+    #[cfg(target_os = "macos")]
+    let tz = macos_user_tz(&user_home(&user)?);
+    #[cfg(all(target_family = "unix", not(target_os = "macos")))]
+    let tz = environment_d_tz(&user_home(&user)?.join(".config/environment.d"));
+    #[cfg(target_family = "windows")]
+    let tz = registry_string_in(
+        ::windows::Win32::System::Registry::HKEY_USERS,
+        &::windows::core::HSTRING::from(format!(r"{}\Environment", windows_user_sid(&user)?)),
+        ::windows::w!("TZ"),
+    )
+    .and_then(|tz| tz.parse_tz());
+
+    tz.or_else(|| system_tz_with(&SystemTzOptions::new().hardened(true)))
+}
+
+#[cfg(all(feature = "std", target_family = "unix"))]
+#[must_use]
+/// Returns the home directory of `user` from the user database.
+fn user_home(user: &User) -> Option<std::path::PathBuf> {
+    use std::os::unix::ffi::OsStrExt;
+
+    /// Size past which the buffer isn't grown anymore.
+    const MAX_BUFFER: usize = 1024 * 1024;
+
+    let name = match user {
+        User::Id(_) => None,
+        User::Name(name) => Some(std::ffi::CString::new(name.as_str()).ok()?),
+    };
+    let mut passwd = core::mem::MaybeUninit::<libc::passwd>::uninit();
+    let mut buffer = vec![0; 16 * 1024];
+    let mut result = core::ptr::null_mut();
+    loop {
+        // Reference: https://man7.org/linux/man-pages/man3/getpwnam.3.html
+        let status = match user {
+            // SAFETY: `passwd` and `buffer` outlive the call, and `buffer.len()` is the actual
+            // length of `buffer`; `result` is only read below, once the call has succeeded
+            User::Id(uid) => unsafe {
+                libc::getpwuid_r(
+                    *uid,
+                    passwd.as_mut_ptr(),
+                    buffer.as_mut_ptr(),
+                    buffer.len(),
+                    &raw mut result,
+                )
+            },
+            // SAFETY: same as above, and `name` is a valid NUL-terminated `CString`
+            // that outlives the call
+            User::Name(_) => unsafe {
+                libc::getpwnam_r(
+                    name.as_deref()?.as_ptr(),
+                    passwd.as_mut_ptr(),
+                    buffer.as_mut_ptr(),
+                    buffer.len(),
+                    &raw mut result,
+                )
+            },
+        };
+        // The entry doesn't fit in the buffer (e.g. with a long list of fields from LDAP)
+        if status == libc::ERANGE && buffer.len() < MAX_BUFFER {
+            buffer.resize(buffer.len() * 2, 0);
+            continue;
+        }
+        if status != 0 || result.is_null() {
+            return None;
+        }
+        break;
+    }
+
+    // SAFETY: `status == 0 && !result.is_null()` was checked above, so `result` points to
+    // the initialized `passwd`, whose strings are NUL-terminated in `buffer`, still alive here
+    let home = unsafe { std::ffi::CStr::from_ptr((*result).pw_dir) };
+    Some(std::ffi::OsStr::from_bytes(home.to_bytes()).into())
+}
+
+#[cfg(all(feature = "std", target_family = "unix", not(target_os = "macos")))]
+#[must_use]
+/// Tries to get the timezone from the `TZ` variable set by the `*.conf` files
+/// of an `environment.d` directory, read in lexical order, the last assignment winning.
+fn environment_d_tz(dir: &std::path::Path) -> Option<Tz> {
+    let mut files = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "conf")
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+
+    files
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .fold(None, |tz, content| {
+            content
+                .lines()
+                .rev()
+                .find_map(|line| line.trim().strip_prefix("TZ="))
+                .map(String::from)
+                .or(tz)
+        })?
+        .parse_tz()
+}
+
+// EXEC ////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(target_family = "unix", feature = "exec-probes"))]
+/// Opt-in probes spawning external commands, used as a last resort.
+mod exec {
+    use super::{Error, ParseMode};
+    use chrono_tz::Tz;
+
+    /// Runs `command` (program and arguments) and returns its standard output if it succeeded.
+    pub fn run(command: &[&str]) -> Option<String> {
+        let (program, args) = command.split_first()?;
+        let output = ::std::process::Command::new(program)
+            .args(args)
+            .output()
+            .ok()?;
+
+        if output.status.success() {
+            String::from_utf8(output.stdout).ok()
+        } else {
+            None
+        }
+    }
+
+    /// Parses a `%z` formatted offset (e.g. `+0130`) to seconds.
+    fn parse_offset(offset: &str) -> Option<i32> {
+        let (sign, digits) = match offset.strip_prefix('+') {
+            Some(digits) => (1, digits),
+            None => (-1, offset.strip_prefix('-')?),
+        };
+
+        if digits.len() != 4 {
+            return None;
+        }
+
+        let hours: i32 = digits.get(..2)?.parse().ok()?;
+        let minutes: i32 = digits.get(2..)?.parse().ok()?;
+        Some(sign * (hours * 3600 + minutes * 60))
+    }
+
+    #[cfg(target_os = "linux")]
+    /// Command printing the environment of the systemd user manager.
+    pub const SYSTEMD_USER_ENVIRONMENT: &[&str] = &["systemctl", "--user", "show-environment"];
+
+    #[cfg(target_os = "linux")]
+    /// Reads `TZ` from the environment of the systemd user manager,
+    /// which some distributions only export there.
+    pub fn systemd_user_environment(output: &str, mode: ParseMode) -> Result<Tz, Error> {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix("TZ="))
+            .map_or(Err(Error::UnknownTimezone), |tz| mode.read(tz))
+    }
+
+    #[cfg(any(target_os = "solaris", target_os = "illumos"))]
+    /// Command printing the `timezone/localtime` property of the `svc:/system/timezone` SMF service.
+    pub const SMF_TIMEZONE: &[&str] = &[
+        "svcprop",
+        "-p",
+        "timezone/localtime",
+        "svc:/system/timezone:default",
+    ];
+
+    #[cfg(any(target_os = "solaris", target_os = "illumos"))]
+    /// Reads the `timezone/localtime` property of the `svc:/system/timezone` SMF service,
+    /// which is where Solaris 11 non-global (and branded) zones store their timezone.
+    pub fn smf_timezone(output: &str, mode: ParseMode) -> Result<Tz, Error> {
+        mode.read(output)
+    }
+
+    /// Command printing the abbreviation and offset of the local timezone.
+    pub const DATE: &[&str] = &["date", "+%Z %z"];
+
+    /// **Heuristic**: guesses a [`Tz`] from the abbreviation and offset
+    /// printed by `date +"%Z %z"` (see [`FromAbbreviation`]), which only runs in lenient mode.
+    pub fn date(output: &str, _mode: ParseMode) -> Result<Tz, Error> {
+        use super::FromAbbreviation;
+
+        let guess = || {
+            let (abbreviation, offset) = output.trim().split_once(' ')?;
+            let offset = chrono::FixedOffset::east_opt(parse_offset(offset)?);
+            Tz::from_abbreviation(abbreviation, offset).first().copied()
+        };
+        guess().ok_or_else(|| Error::unparsed(output))
+    }
+}
+
+#[cfg(all(target_family = "unix", feature = "exec-probes"))]
+#[derive(Debug, Clone)]
+/// Provider running a command and parsing its standard output (see the `exec-probes` feature).
+pub struct ExecProvider {
+    source: DetectionSource,
+    command: &'static [&'static str],
+    parse: fn(&str, ParseMode) -> Result<Tz, Error>,
+    host: Host,
+}
+
+#[cfg(all(target_family = "unix", feature = "exec-probes"))]
+impl ExecProvider {
+    #[cfg(target_os = "linux")]
+    #[must_use]
+    /// Returns a provider reading `TZ` from `systemctl --user show-environment`.
+    pub fn systemd_user_environment() -> Self {
+        Self {
+            source: DetectionSource::SystemdUserEnvironment,
+            command: exec::SYSTEMD_USER_ENVIRONMENT,
+            parse: exec::systemd_user_environment,
+            host: Host::Os,
+        }
+    }
+
+    #[cfg(any(target_os = "solaris", target_os = "illumos"))]
+    #[must_use]
+    /// Returns a provider reading the timezone of the `svc:/system/timezone` SMF service.
+    pub fn smf_timezone() -> Self {
+        Self {
+            source: DetectionSource::SmfTimezone,
+            command: exec::SMF_TIMEZONE,
+            parse: exec::smf_timezone,
+            host: Host::Os,
+        }
+    }
+
+    #[must_use]
+    /// Returns a provider guessing the timezone from `date +"%Z %z"` (**heuristic**).
+    pub fn date() -> Self {
+        Self {
+            source: DetectionSource::DateCommand,
+            command: exec::DATE,
+            parse: exec::date,
+            host: Host::Os,
+        }
+    }
+
+    /// Runs the command on `host`.
+    fn on(self, host: Host) -> Self {
+        Self { host, ..self }
+    }
+}
+
+#[cfg(all(target_family = "unix", feature = "exec-probes"))]
+impl TzProvider for ExecProvider {
+    fn source(&self) -> DetectionSource {
+        self.source
+    }
+
+    fn read(&self) -> Reading {
+        self.read_in(ParseMode::default())
+    }
+
+    fn read_in(&self, mode: ParseMode) -> Reading {
+        self.host.run(self.command).map_or(
+            Reading {
+                raw: None,
+                tz: Err(Error::NoSource),
+            },
+            |output| Reading {
+                tz: (self.parse)(&output, mode),
+                raw: Some(output),
+            },
+        )
+    }
+}
+
+// WINDOWS /////////////////////////////////////////////////////////////////////
+
+#[cfg(any(
+    all(feature = "std", target_family = "windows"),
+    feature = "windows-zones"
+))]
+include!(concat!(env!("OUT_DIR"), "/windows_zones.rs"));
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+trait WindowsUtf16 {
+    #[must_use]
+    /// Tries to cast Windows UTF-16 to valid UTF-8.
+    fn as_utf8(&self) -> Option<String>;
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+impl<const N: usize> WindowsUtf16 for [u16; N] {
+    fn as_utf8(&self) -> Option<String> {
+        Some(String::from_utf16_lossy(self.split(|x| *x == 0).next()?))
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Tries to read a `REG_SZ` value from the `HKEY_LOCAL_MACHINE` registry hive.
+fn registry_string<P0, P1>(subkey: P0, value: P1) -> Option<String>
+where
+    P0: ::windows::core::IntoParam<::windows::core::PCWSTR>,
+    P1: ::windows::core::IntoParam<::windows::core::PCWSTR>,
+{
+    registry_string_in(
+        ::windows::Win32::System::Registry::HKEY_LOCAL_MACHINE,
+        subkey,
+        value,
+    )
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Tries to read a `REG_SZ` value from the `root` registry hive.
+///
+/// Always reads the 64-bit registry view, so that 32-bit processes running under WOW64 don't
+/// see redirected keys.
+fn registry_string_in<P0, P1>(
+    root: ::windows::Win32::System::Registry::HKEY,
+    subkey: P0,
+    value: P1,
+) -> Option<String>
+where
+    P0: ::windows::core::IntoParam<::windows::core::PCWSTR>,
+    P1: ::windows::core::IntoParam<::windows::core::PCWSTR>,
+{
+    use ::windows::Win32::{
+        Foundation::ERROR_SUCCESS,
+        System::Registry::{RegGetValueW, RRF_RT_REG_SZ, RRF_SUBKEY_WOW6464KEY},
+    };
+
+    let mut buffer = [0_u16; 256];
+    let mut size = u32::try_from(::std::mem::size_of_val(&buffer)).ok()?;
+
+    // Reference: https://learn.microsoft.com/en-us/windows/win32/api/winreg/nf-winreg-reggetvaluew
+    let status = unsafe {
+        RegGetValueW(
+            root,
+            subkey,
+            value,
+            RRF_RT_REG_SZ | RRF_SUBKEY_WOW6464KEY,
+            None,
+            Some(buffer.as_mut_ptr().cast()),
+            Some(::std::ptr::addr_of_mut!(size)),
+        )
+    };
+
+    if status == ERROR_SUCCESS {
+        buffer.as_utf8().filter(|x| !x.is_empty())
+    } else {
+        None
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Returns the SID of `user` as a string (e.g. `S-1-5-21-…-1001`), naming its registry hive
+/// under `HKEY_USERS`.
+fn windows_user_sid(user: &User) -> Option<String> {
+    use ::windows::{
+        core::{HSTRING, PCWSTR, PWSTR},
+        Win32::{
+            Foundation::{HLOCAL, PSID},
+            Security::{Authorization::ConvertSidToStringSidW, LookupAccountNameW, SID_NAME_USE},
+            System::Memory::LocalFree,
+        },
+    };
+
+    let User::Name(name) = user;
+
+    // `SECURITY_MAX_SID_SIZE`
+    let mut sid = [0_u8; 68];
+    let mut sid_size = u32::try_from(sid.len()).ok()?;
+    let mut domain = [0_u16; 256];
+    let mut domain_size = u32::try_from(domain.len()).ok()?;
+    let mut kind = SID_NAME_USE::default();
+    let psid = PSID(sid.as_mut_ptr().cast());
+    // Reference: https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-lookupaccountnamew
+    let found = unsafe {
+        LookupAccountNameW(
+            PCWSTR::null(),
+            &HSTRING::from(name.as_str()),
+            psid,
+            &raw mut sid_size,
+            PWSTR(domain.as_mut_ptr()),
+            &raw mut domain_size,
+            &raw mut kind,
+        )
+    };
+    if !found.as_bool() {
+        return None;
+    }
+
+    let mut string = PWSTR::null();
+    // Reference: https://learn.microsoft.com/en-us/windows/win32/api/sddl/nf-sddl-convertsidtostringsidw
+    if !unsafe { ConvertSidToStringSidW(psid, &raw mut string) }.as_bool() {
+        return None;
+    }
+    let sid = unsafe { string.to_string() }.ok();
+    let _ = unsafe { LocalFree(HLOCAL(string.0 as isize)) };
+    sid
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+/// Registry key of the Windows timezones, under `HKEY_LOCAL_MACHINE`.
+const TIME_ZONES: &str = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion\Time Zones";
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Tries to load a localized (`MUI_*`) string value from the `HKEY_LOCAL_MACHINE` registry hive,
+/// in the UI language of the current user.
+fn registry_mui_string<P>(subkey: &::windows::core::HSTRING, value: P) -> Option<String>
+where
+    P: ::windows::core::IntoParam<::windows::core::PCWSTR>,
+{
+    use ::windows::{
+        core::PWSTR,
+        Win32::{
+            Foundation::ERROR_SUCCESS,
+            System::Registry::{
+                RegCloseKey, RegLoadMUIStringW, RegOpenKeyExW, HKEY, HKEY_LOCAL_MACHINE, KEY_READ,
+                KEY_WOW64_64KEY,
+            },
+        },
+    };
+
+    let mut key = HKEY::default();
+    // Reference: https://learn.microsoft.com/en-us/windows/win32/api/winreg/nf-winreg-regopenkeyexw
+    let status = unsafe {
+        RegOpenKeyExW(
+            HKEY_LOCAL_MACHINE,
+            subkey,
+            0,
+            KEY_READ | KEY_WOW64_64KEY,
+            ::std::ptr::addr_of_mut!(key),
+        )
+    };
+    if status != ERROR_SUCCESS {
+        return None;
+    }
+
+    let mut buffer = [0_u16; 256];
+    // Reference: https://learn.microsoft.com/en-us/windows/win32/api/winreg/nf-winreg-regloadmuistringw
+    let status = unsafe {
+        RegLoadMUIStringW(
+            key,
+            value,
+            PWSTR(buffer.as_mut_ptr()),
+            u32::try_from(::std::mem::size_of_val(&buffer)).unwrap_or_default(),
+            None,
+            0,
+            None,
+        )
+    };
+
+    unsafe { RegCloseKey(key) };
+
+    if status == ERROR_SUCCESS {
+        buffer.as_utf8().filter(|x| !x.is_empty())
+    } else {
+        None
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Tries to find the registry key name of the Windows timezone matching `predicate`
+/// (called with the full path of each key), by enumerating
+/// `HKEY_LOCAL_MACHINE\SOFTWARE\Microsoft\Windows NT\CurrentVersion\Time Zones`.
+fn registry_zone_by<F>(predicate: F) -> Option<String>
+where
+    F: Fn(&::windows::core::HSTRING) -> bool,
+{
+    use ::windows::{
+        core::{HSTRING, PWSTR},
+        Win32::{
+            Foundation::ERROR_SUCCESS,
+            System::Registry::{
+                RegCloseKey, RegEnumKeyExW, RegOpenKeyExW, HKEY, HKEY_LOCAL_MACHINE, KEY_READ,
+                KEY_WOW64_64KEY,
+            },
+        },
+    };
+
+    let mut key = HKEY::default();
+    // Reference: https://learn.microsoft.com/en-us/windows/win32/api/winreg/nf-winreg-regopenkeyexw
+    let status = unsafe {
+        RegOpenKeyExW(
+            HKEY_LOCAL_MACHINE,
+            &HSTRING::from(TIME_ZONES),
+            0,
+            KEY_READ | KEY_WOW64_64KEY,
+            ::std::ptr::addr_of_mut!(key),
+        )
+    };
+    if status != ERROR_SUCCESS {
+        return None;
+    }
+
+    let mut found = None;
+    for index in 0.. {
+        let mut name = [0_u16; 256];
+        let mut len = u32::try_from(name.len()).unwrap_or_default();
+        // Reference: https://learn.microsoft.com/en-us/windows/win32/api/winreg/nf-winreg-regenumkeyexw
+        let status = unsafe {
+            RegEnumKeyExW(
+                key,
+                index,
+                PWSTR(name.as_mut_ptr()),
+                ::std::ptr::addr_of_mut!(len),
+                None,
+                PWSTR::null(),
+                None,
+                None,
+            )
+        };
+        if status != ERROR_SUCCESS {
+            break;
+        }
+
+        let Some(zone) = name.as_utf8() else {
+            continue;
+        };
+
+        if predicate(&HSTRING::from(format!(r"{TIME_ZONES}\{zone}"))) {
+            found = Some(zone);
+            break;
+        }
+    }
+
+    unsafe { RegCloseKey(key) };
+
+    found
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Returns whether the current process runs in an app container (e.g. a packaged UWP app).
+fn is_app_container() -> bool {
+    use ::windows::Win32::{
+        Foundation::{CloseHandle, HANDLE},
+        Security::{GetTokenInformation, TokenIsAppContainer, TOKEN_QUERY},
+        System::Threading::{GetCurrentProcess, OpenProcessToken},
+    };
+
+    // Reference: https://learn.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-openprocesstoken
+    let mut token = HANDLE::default();
+    if !unsafe {
+        OpenProcessToken(
+            GetCurrentProcess(),
+            TOKEN_QUERY,
+            ::std::ptr::addr_of_mut!(token),
+        )
+    }
+    .as_bool()
+    {
+        return false;
+    }
+
+    // Reference: https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-gettokeninformation
+    let mut is_app_container = 0_u32;
+    let mut len = 0_u32;
+    let status = unsafe {
+        GetTokenInformation(
+            token,
+            TokenIsAppContainer,
+            Some(::std::ptr::addr_of_mut!(is_app_container).cast()),
+            u32::try_from(::std::mem::size_of_val(&is_app_container)).unwrap_or_default(),
+            ::std::ptr::addr_of_mut!(len),
+        )
+    };
+
+    unsafe { CloseHandle(token) };
+
+    status.as_bool() && is_app_container != 0
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Returns whether the current process runs under Wine, whose `ntdll` exports `wine_get_version`.
+fn is_wine() -> bool {
+    use ::windows::Win32::System::LibraryLoader::{GetModuleHandleW, GetProcAddress};
+
+    // Reference: https://wiki.winehq.org/Developer_FAQ#How_can_I_detect_Wine.3F
+    unsafe { GetModuleHandleW(::windows::w!("ntdll.dll")) }.is_ok_and(|ntdll| {
+        unsafe { GetProcAddress(ntdll, ::windows::s!("wine_get_version")) }.is_some()
+    })
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Tries to get the timezone of the unix host running Wine, whose root is mapped to the `Z:` drive.
+fn wine_host_tz() -> Option<Tz> {
+    use ::std::{env, fs};
+
+    env::var("TZ")
+        .ok()
+        .filter(|_| !cfg!(feature = "hardened"))
+        .and_then(|tz| tz.parse_tz())
+        .or_else(|| {
+            fs::read_to_string(r"Z:\etc\timezone")
+                .ok()
+                .and_then(|tz| tz.parse_tz())
+        })
+        .or_else(|| {
+            let target = fs::read_link(r"Z:\etc\localtime").ok()?;
+            let target = target.to_string_lossy().replace('\\', "/");
+            let (_, name) = target.rsplit_once("/zoneinfo/")?;
+            name.trim_start_matches("posix/")
+                .trim_start_matches("right/")
+                .parse_tz()
+        })
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Tries to get the territory (ISO 3166-1 alpha-2 code) of the current user from Windows.
+fn windows_territory() -> Option<String> {
+    use ::windows::Win32::Globalization::{
+        GetGeoInfoW, GetUserDefaultGeoName, GetUserGeoID, GEOCLASS_NATION, GEOID_NOT_AVAILABLE,
+        GEO_ISO2,
+    };
+
+    // Reference: https://learn.microsoft.com/en-us/windows/win32/api/winnls/nf-winnls-getuserdefaultgeoname
+    let mut name = [0_u16; 16];
+    if unsafe { GetUserDefaultGeoName(&mut name) } > 0 {
+        if let Some(territory) = name.as_utf8().filter(|x| is_territory(x)) {
+            return Some(territory);
+        }
+    }
+
+    // Legacy: `GetUserDefaultGeoName` is only available since Windows 10 (1709)
+    // Reference: https://learn.microsoft.com/en-us/windows/win32/api/winnls/nf-winnls-getusergeoid
+    let id = unsafe { GetUserGeoID(GEOCLASS_NATION.0.unsigned_abs()) };
+    if id == GEOID_NOT_AVAILABLE {
+        return None;
+    }
+
+    let mut name = [0_u16; 16];
+    if unsafe { GetGeoInfoW(id, GEO_ISO2.0.unsigned_abs(), Some(&mut name), 0) } > 0 {
+        name.as_utf8().filter(|x| is_territory(x))
+    } else {
+        None
+    }
+}
+
+#[cfg(any(
+    all(feature = "std", target_family = "windows"),
+    feature = "windows-zones"
+))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+/// Version of a CLDR `WindowsZones` dataset (see [`WindowsZonesVersion::bundled()`]).
+pub struct WindowsZonesVersion {
+    release: &'static str,
+    build_date: &'static str,
+    version: (&'static str, &'static str),
+    sha256: &'static str,
+}
+
+#[cfg(any(
+    all(feature = "std", target_family = "windows"),
+    feature = "windows-zones"
+))]
+impl WindowsZonesVersion {
+    #[must_use]
+    /// Returns the version of the `WindowsZones` dataset embedded in the binary.
+    pub const fn bundled() -> &'static Self {
+        &WINDOWS_ZONES_VERSION
+    }
+
+    #[must_use]
+    /// Returns the CLDR release of the dataset (e.g. `release-44`).
+    pub const fn release(&self) -> &'static str {
+        self.release
+    }
+
+    #[must_use]
+    /// Returns the date at which the dataset was built into the binary.
+    ///
+    /// For reproducible builds, it is taken from the `SOURCE_DATE_EPOCH` env variable when set.
+    pub fn build_date(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.build_date.parse().ok()
+    }
+
+    #[must_use]
+    /// Returns the version of the Windows timezones of the dataset (`otherVersion`).
+    pub const fn windows_version(&self) -> &'static str {
+        self.version.0
+    }
+
+    #[must_use]
+    /// Returns the version of the IANA Time Zone Database of the dataset (`typeVersion`).
+    pub const fn tzdata_version(&self) -> &'static str {
+        self.version.1
+    }
+
+    #[must_use]
+    /// Returns the hexadecimal SHA-256 digest of the dataset (the `windowsZones.xml` file).
+    ///
+    /// Unlike a `Hash` of the parsed data, it is stable across builds and Rust releases,
+    /// and can be checked against the upstream file.
+    pub const fn sha256(&self) -> &'static str {
+        self.sha256
+    }
+}
+
+#[cfg(any(
+    all(feature = "std", target_family = "windows"),
+    feature = "windows-zones"
+))]
+impl core::fmt::Display for WindowsZonesVersion {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "CLDR {} (Windows {}, tzdata {})",
+            self.release,
+            self.windows_version(),
+            self.tzdata_version()
+        )
+    }
+}
+
+#[cfg(any(
+    all(feature = "std", target_family = "windows"),
+    feature = "windows-zones"
+))]
+/// `WindowsZones` mappings sorted by `(zone, territory)`,
+/// with an index of `(IANA name, position)` sorted by IANA name.
+struct WindowsZonesIndex {
+    zones: alloc::borrow::Cow<'static, [&'static WindowsTz]>,
+    by_iana: alloc::borrow::Cow<'static, [(&'static str, usize)]>,
+}
+
+#[cfg(any(
+    all(feature = "std", target_family = "windows"),
+    feature = "windows-zones"
+))]
+impl WindowsZonesIndex {
+    #[cfg(feature = "runtime-cldr")]
+    /// Sorts and indexes `zones`.
+    fn new(mut zones: Vec<&'static WindowsTz>) -> Self {
+        zones.sort_by(|a, b| (a.zone.as_str(), a.territory).cmp(&(b.zone.as_str(), b.territory)));
+
+        let mut by_iana: Vec<(&'static str, usize)> = zones
+            .iter()
+            .enumerate()
+            .flat_map(|(index, zone)| zone.iana.iter().map(move |name| (*name, index)))
+            .collect();
+        by_iana.sort_unstable();
+
+        Self {
+            zones: zones.into(),
+            by_iana: by_iana.into(),
+        }
+    }
+
+    /// Returns the mappings of the Windows `zone`, the default one first.
+    fn by_zone(&self, zone: &str) -> &[&'static WindowsTz] {
+        let start = self.zones.partition_point(|x| x.zone.as_str() < zone);
+        let end = start + self.zones[start..].partition_point(|x| x.zone.as_str() == zone);
+        &self.zones[start..end]
+    }
+
+    /// Returns the mapping of the `(zone, territory)` pair, or the default one of the `zone`.
+    fn get(&self, zone: &str, territory: Option<&str>) -> Option<&'static WindowsTz> {
+        let zones = self.by_zone(zone);
+        territory.map_or_else(
+            || zones.first().copied(),
+            |territory| {
+                zones
+                    .binary_search_by(|x| x.territory.cmp(&Some(territory)))
+                    .ok()
+                    .map(|index| zones[index])
+            },
+        )
+    }
+
+    /// Returns the mappings of the IANA timezone `name`.
+    fn by_iana<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'static WindowsTz> + 'a {
+        let start = self.by_iana.partition_point(|(x, _)| *x < name);
+        self.by_iana[start..]
+            .iter()
+            .take_while(move |(x, _)| *x == name)
+            .map(move |(_, index)| self.zones[*index])
+    }
+}
+
+#[cfg(feature = "runtime-cldr")]
+mod cldr;
+
+#[cfg(feature = "runtime-cldr")]
+/// `WindowsZones` dataset loaded at runtime.
+struct LoadedWindowsZones {
+    version: (&'static str, &'static str),
+    zones: WindowsZonesIndex,
+}
+
+#[cfg(feature = "runtime-cldr")]
+/// `WindowsZones` dataset loaded with [`WindowsTz::load_from_xml()`],
+/// which takes precedence over the bundled one.
+static LOADED_WINDOWS_ZONES: std::sync::RwLock<Option<std::sync::Arc<LoadedWindowsZones>>> =
+    std::sync::RwLock::new(None);
+
+#[cfg(feature = "runtime-cldr")]
+/// Values loaded at runtime, which are handed out as `&'static` references.
+///
+/// Each distinct value is allocated once and kept for the lifetime of the process,
+/// so loading a dataset again only allocates the mappings which changed.
+struct Interned {
+    strs: alloc::collections::BTreeSet<&'static str>,
+    names: alloc::collections::BTreeSet<&'static [&'static str]>,
+    zones: alloc::collections::BTreeSet<&'static WindowsTz>,
+}
+
+#[cfg(feature = "runtime-cldr")]
+static INTERNED: std::sync::Mutex<Interned> = std::sync::Mutex::new(Interned {
+    strs: alloc::collections::BTreeSet::new(),
+    names: alloc::collections::BTreeSet::new(),
+    zones: alloc::collections::BTreeSet::new(),
+});
+
+#[cfg(feature = "runtime-cldr")]
+/// Returns the interned copy of `value`, allocating it on first use.
+fn intern<T: Ord + ?Sized>(
+    set: &mut alloc::collections::BTreeSet<&'static T>,
+    value: Box<T>,
+) -> &'static T {
+    if let Some(interned) = set.get(&*value) {
+        return interned;
+    }
+
+    let value = Box::leak(value);
+    set.insert(value);
+    value
+}
+
+#[cfg(any(
+    all(feature = "std", target_family = "windows"),
+    feature = "windows-zones"
+))]
+/// Either the bundled `WindowsZones` dataset or the one loaded at runtime.
+enum WindowsZonesRef {
+    Bundled(&'static WindowsZonesIndex),
+    #[cfg(feature = "runtime-cldr")]
+    Loaded(std::sync::Arc<LoadedWindowsZones>),
+}
+
+#[cfg(any(
+    all(feature = "std", target_family = "windows"),
+    feature = "windows-zones"
+))]
+impl core::ops::Deref for WindowsZonesRef {
+    type Target = WindowsZonesIndex;
+
+    fn deref(&self) -> &WindowsZonesIndex {
+        match self {
+            Self::Bundled(zones) => zones,
+            #[cfg(feature = "runtime-cldr")]
+            Self::Loaded(loaded) => &loaded.zones,
+        }
+    }
+}
+
+#[cfg(any(
+    all(feature = "std", target_family = "windows"),
+    feature = "windows-zones"
+))]
+/// Returns the known datasets: the one loaded at runtime first (if any), then the bundled one.
+fn windows_zones() -> impl Iterator<Item = WindowsZonesRef> {
+    #[cfg(feature = "runtime-cldr")]
+    let loaded = LOADED_WINDOWS_ZONES
+        .read()
+        .ok()
+        .and_then(|loaded| loaded.clone())
+        .map(WindowsZonesRef::Loaded);
+
+    #[cfg(not(feature = "runtime-cldr"))]
+    let loaded = None;
+
+    loaded
+        .into_iter()
+        .chain([WindowsZonesRef::Bundled(&WINDOWS_ZONES)])
+}
+
+#[cfg(any(
+    all(feature = "std", target_family = "windows"),
+    feature = "windows-zones"
+))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Known Microsoft Windows timezone.
+pub struct WindowsTz {
+    zone: WindowsZone,
+    territory: Option<&'static str>,
+    iana: &'static [&'static str],
+}
+
+#[cfg(any(
+    all(feature = "std", target_family = "windows"),
+    feature = "windows-zones"
+))]
+impl WindowsTz {
+    #[must_use]
+    /// Returns a `WindowsTz` **only if it is registered in `WindowsZones` dataset**,
+    /// looking up the `(zone, territory)` pair.
+    ///
+    /// If no `territory` is provided, returns the first known `WindowsTz`,
+    /// with a matching the `zone` (i.e. its default mapping).
+    pub fn get(zone: &str, territory: Option<&str>) -> Option<&'static Self> {
+        windows_zones().find_map(|zones| zones.get(zone, territory))
+    }
+
+    #[must_use]
+    /// Returns the Windows zone (e.g. [`WindowsZone::RomanceStandardTime`]).
+    pub const fn zone(&self) -> WindowsZone {
+        self.zone
+    }
+
+    #[must_use]
+    /// Returns the territory (ISO 3166-1 alpha-2 code, `001` for the default mapping
+    /// or `ZZ` for the unknown territory) if any.
+    pub const fn territory(&self) -> Option<&'static str> {
+        self.territory
+    }
+
+    #[must_use]
+    /// Returns the IANA names mapped to this `WindowsTz`, the first one being preferred.
+    pub const fn iana(&self) -> &'static [&'static str] {
+        self.iana
+    }
+
+    #[must_use]
+    /// Returns all the [`Tz`] mapped to this `WindowsTz`, the first one being preferred.
+    pub fn timezones(&self) -> Vec<Tz> {
+        self.iana
+            .iter()
+            .filter_map(|name| name.parse().ok())
+            .collect()
+    }
+
+    /// Returns all the known `WindowsTz` (i.e. the whole `WindowsZones` mapping).
+    pub fn all() -> impl Iterator<Item = &'static Self> {
+        let mut seen = alloc::collections::BTreeSet::new();
+        let mut zones: Vec<&'static Self> = windows_zones()
+            .flat_map(|zones| zones.zones.to_vec())
+            .collect();
+
+        // Mappings loaded at runtime take precedence over the bundled ones
+        zones.retain(|x| seen.insert((x.zone, x.territory)));
+        zones.into_iter()
+    }
+
+    #[must_use]
+    /// Returns all the known `WindowsTz` mapped to the IANA timezone `tz`.
+    pub fn from_iana(tz: &Tz) -> Vec<&'static Self> {
+        let mut seen = alloc::collections::BTreeSet::new();
+        let mut zones: Vec<&'static Self> = windows_zones()
+            .flat_map(|zones| zones.by_iana(tz.name()).collect::<Vec<_>>())
+            .collect();
+
+        // Mappings loaded at runtime take precedence over the bundled ones
+        zones.retain(|x| seen.insert((x.zone, x.territory)));
+        zones
+    }
+
+    /// Returns the territories having a specific mapping for the Windows `zone`
+    /// (including `001` for the default mapping first).
+    pub fn territories(zone: &str) -> impl Iterator<Item = &'static str> {
+        let mut seen = alloc::collections::BTreeSet::new();
+        let mut territories: Vec<&'static str> = windows_zones()
+            .flat_map(|zones| zones.by_zone(zone).to_vec())
+            .filter_map(|x| x.territory)
+            .collect();
+
+        // Mappings loaded at runtime may duplicate the bundled ones
+        territories.retain(|x| seen.insert(*x));
+        territories.into_iter()
+    }
+
+    #[cfg(feature = "std")]
+    #[must_use]
+    /// Returns a `WindowsTz` matching the `zone` in the territory of the current user
+    /// (see [`system_territory()`]), falling back to the default mapping of the `zone`
+    /// (see [`WindowsTz::get()`]).
+    ///
+    /// For instance, `US Mountain Standard Time` maps to `America/Creston`
+    /// for a user located in Canada rather than to `America/Phoenix`.
+    pub fn get_for_user(zone: &str) -> Option<&'static Self> {
+        system_territory()
+            .and_then(|territory| Self::get(zone, Some(&territory)))
+            .or_else(|| Self::get(zone, None))
+    }
+
+    #[cfg(all(feature = "std", target_family = "windows"))]
+    #[must_use]
+    /// Returns a `WindowsTz` from a Windows display name, either in English from the registry
+    /// `Display` value (e.g. `(UTC+01:00) Brussels, Copenhagen, Madrid, Paris`) or localized
+    /// in the UI language of the current user (`MUI_Display`), see [`WindowsTz::get_for_user()`].
+    ///
+    /// As display names are read from the registry, only those of the zones installed
+    /// on the system can be resolved.
+    pub fn from_display_name(name: &str) -> Option<&'static Self> {
+        let name = name.trim();
+        registry_zone_by(|key| {
+            registry_string(key, ::windows::w!("Display")).as_deref() == Some(name)
+                || registry_mui_string(key, ::windows::w!("MUI_Display")).as_deref() == Some(name)
+        })
+        .and_then(|zone| Self::get_for_user(&zone))
+    }
+
+    #[must_use]
+    /// Returns the build date of the bundled `WindowsZones` dataset.
+    pub fn build_date() -> Option<chrono::DateTime<chrono::Utc>> {
+        WindowsZonesVersion::bundled().build_date()
+    }
+
+    #[must_use]
+    /// Returns a hash of the bundled `WindowsZones` dataset: the first 64 bits
+    /// of its SHA-256 digest (see [`WindowsZonesVersion::sha256()`]).
+    pub fn hash() -> Option<u64> {
+        WindowsZonesVersion::bundled()
+            .sha256()
+            .get(..16)
+            .and_then(|prefix| u64::from_str_radix(prefix, 16).ok())
+    }
+
+    #[must_use]
+    /// Returns the version of the bundled `WindowsZones` dataset.
+    pub fn version() -> (&'static str, &'static str) {
+        WINDOWS_ZONES_VERSION.version
+    }
+
+    #[cfg(feature = "runtime-cldr")]
+    /// Loads a (newer) `windowsZones.xml` document at runtime, whose mappings take
+    /// precedence over the bundled ones, and returns the number of loaded mappings.
+    ///
+    /// Loading again replaces the previously loaded dataset.
+    /// Zones missing from the bundled dataset are kept as [`WindowsZone::Other`],
+    /// whereas IANA names unknown to `chrono-tz` are skipped (see [`WindowsTz::iana()`]).
+    ///
+    /// Note that the loaded mappings are handed out as `&'static` references, so they are
+    /// kept for the lifetime of the process: each distinct mapping is allocated once,
+    /// which bounds the memory used by repeated (e.g. periodic) updates.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidCldrData`] if the document can't be deserialized.
+    pub fn load_from_xml(xml: &str) -> Result<usize, Error> {
+        let mut data = cldr::WindowsZonesData::from_xml(xml).map_err(|_| Error::InvalidCldrData)?;
+        data.retain_known_zones();
+        let cldr::MapTimezones {
+            other_version,
+            type_version,
+            zones,
+        } = data.windows_zones.timezones;
+
+        let mut interned = INTERNED
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let Interned {
+            strs,
+            names,
+            zones: records,
+        } = &mut *interned;
+
+        let zones: Vec<&'static Self> = zones
+            .into_iter()
+            .map(
+                |cldr::MapZone {
+                     zone,
+                     territory,
+                     iana,
+                 }| {
+                    let zone = zone.parse().unwrap_or_else(|_| {
+                        WindowsZone::Other(intern(strs, zone.into_boxed_str()))
+                    });
+                    let territory = territory.map(|x| intern(strs, x.into_boxed_str()));
+                    let iana: Box<[&'static str]> = iana
+                        .into_iter()
+                        .map(|x| intern(strs, x.into_boxed_str()))
+                        .collect();
+
+                    let record = Self {
+                        zone,
+                        territory,
+                        iana: intern(names, iana),
+                    };
+                    intern(records, Box::new(record))
+                },
+            )
+            .collect();
+
+        let loaded = LoadedWindowsZones {
+            version: (
+                intern(strs, other_version.into_boxed_str()),
+                intern(strs, type_version.into_boxed_str()),
+            ),
+            zones: WindowsZonesIndex::new(zones),
+        };
+        drop(interned);
+
+        let count = loaded.zones.zones.len();
+        *LOADED_WINDOWS_ZONES
+            .write()
+            .map_err(|_| Error::InvalidCldrData)? = Some(std::sync::Arc::new(loaded));
+
+        Ok(count)
+    }
+
+    #[cfg(feature = "runtime-cldr")]
+    /// Loads a (newer) `windowsZones.xml` file at runtime (see [`WindowsTz::load_from_xml()`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] (or [`Error::PermissionDenied`]) if the file can't be read
+    /// or [`Error::InvalidCldrData`] if it can't be deserialized.
+    pub fn load_from_path<P: AsRef<std::path::Path>>(path: P) -> Result<usize, Error> {
+        let path = path.as_ref();
+        let xml = std::fs::read_to_string(path).map_err(|err| Error::io(path, &err))?;
+        Self::load_from_xml(&xml)
+    }
+
+    #[cfg(feature = "runtime-cldr")]
+    #[must_use]
+    /// Returns the version of the `WindowsZones` dataset loaded at runtime, if any.
+    pub fn loaded_version() -> Option<(&'static str, &'static str)> {
+        LOADED_WINDOWS_ZONES
+            .read()
+            .ok()
+            .and_then(|loaded| loaded.as_ref().map(|loaded| loaded.version))
+    }
+
+    #[cfg(feature = "runtime-cldr")]
+    /// Compares the bundled `WindowsZones` dataset against a (newer) `windowsZones.xml`
+    /// document and returns the mappings which were added, removed or changed,
+    /// e.g. to decide whether a rebuild is worth it.
+    ///
+    /// Unlike [`WindowsTz::load_from_xml()`], IANA names unknown to `chrono-tz`
+    /// are compared as well, since they call for a rebuild against a newer tzdata.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidCldrData`] if the document can't be deserialized.
+    pub fn diff_xml(xml: &str) -> Result<Vec<WindowsZonesChange>, Error> {
+        let data = cldr::WindowsZonesData::from_xml(xml).map_err(|_| Error::InvalidCldrData)?;
+        let zones = data.windows_zones.timezones.zones;
+
+        let mut changes = Vec::new();
+
+        // Mappings without territory are added by the build script, not part of the dataset
+        for bundled in WINDOWS_ZONES.zones.iter().filter(|x| x.territory.is_some()) {
+            let Some(other) = zones.iter().find(|x| {
+                x.zone == bundled.zone.as_str() && x.territory.as_deref() == bundled.territory
+            }) else {
+                changes.push(WindowsZonesChange::Removed(bundled));
+                continue;
+            };
+
+            if other.iana != bundled.iana {
+                changes.push(WindowsZonesChange::Changed {
+                    from: bundled,
+                    iana: other.iana.clone(),
+                });
+            }
+        }
+
+        for other in zones {
+            let territory = other.territory.as_deref();
+            if territory.is_some() && WINDOWS_ZONES.get(&other.zone, territory).is_none() {
+                changes.push(WindowsZonesChange::Added {
+                    iana: other.iana,
+                    zone: other.zone,
+                    territory: other.territory,
+                });
+            }
+        }
+
+        Ok(changes)
+    }
+}
+
+#[cfg(feature = "runtime-cldr")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Change of a mapping between the bundled `WindowsZones` dataset and another one
+/// (see [`WindowsTz::diff_xml()`]).
+pub enum WindowsZonesChange {
+    /// Mapping only present in the other dataset.
+    Added {
+        zone: String,
+        territory: Option<String>,
+        iana: Vec<String>,
+    },
+    /// Mapping only present in the bundled dataset.
+    Removed(&'static WindowsTz),
+    /// Mapping whose IANA names differ in the other dataset.
+    Changed {
+        from: &'static WindowsTz,
+        iana: Vec<String>,
+    },
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Tries to map a custom Windows zone absent from `WindowsZones` (e.g. `UTC+05`, `UTC-02:00`)
+/// to the matching `Etc/GMT` timezone, whose sign is inverted (e.g. `Etc/GMT-5`).
+fn custom_utc_zone(zone: &str) -> Option<Tz> {
+    let offset = zone.trim().strip_prefix("UTC")?;
+    let (sign, offset) = if let Some(offset) = offset.strip_prefix('+') {
+        ('-', offset)
+    } else {
+        ('+', offset.strip_prefix('-')?)
+    };
+
+    let (hours, minutes) = offset.split_once(':').unwrap_or((offset, "00"));
+    minutes.parse::<u8>().ok().filter(|minutes| *minutes == 0)?;
+    let hours = hours.parse::<u8>().ok()?;
+
+    alloc::format!("Etc/GMT{sign}{hours}").parse().ok()
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Tries to map a Windows zone to a [`Tz`] in the territory of the current user
+/// (see [`WindowsTz::get_for_user()`]), including custom `UTC+X` zones.
+fn windows_zone_tz(zone: &str) -> Option<Tz> {
+    WindowsTz::get_for_user(zone)
+        .and_then(|windows_tz| windows_tz.try_into().ok())
+        .or_else(|| custom_utc_zone(zone))
+}
+
+#[cfg(any(
+    all(feature = "std", target_family = "windows"),
+    feature = "windows-zones"
+))]
+impl TryFrom<&WindowsTz> for Tz {
+    type Error = Error;
+
+    fn try_from(tz: &WindowsTz) -> Result<Self, Self::Error> {
+        // This should be infaillible as timezone validity is checked while building data
+        tz.iana[0].parse().map_err(|_| Error::UnknownTimezone)
+    }
+}
+
+#[cfg(any(
+    all(feature = "std", target_family = "windows"),
+    feature = "windows-zones"
+))]
+impl TryFrom<&Tz> for WindowsTz {
+    type Error = Error;
+
+    fn try_from(tz: &Tz) -> Result<Self, Self::Error> {
+        Self::from_iana(tz)
+            .first()
+            .copied()
+            .cloned()
+            .ok_or(Error::UnknownTimezone)
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Details of the current Windows timezone, as returned by `GetDynamicTimeZoneInformation`.
+///
+/// Biases are in minutes, such that `UTC = local time + bias`.
+pub struct WindowsSystemTzInfo {
+    /// Windows zone name (e.g. `Romance Standard Time`), may be empty on older versions of Windows.
+    pub key_name: String,
+    /// Current bias, not including the standard or daylight bias.
+    pub bias: i32,
+    /// Localized name of the standard time (e.g. `Romance Standard Time`).
+    pub standard_name: String,
+    /// Bias added to `bias` during standard time.
+    pub standard_bias: i32,
+    /// Localized name of the daylight saving time (e.g. `Romance Daylight Time`).
+    pub daylight_name: String,
+    /// Bias added to `bias` during daylight saving time.
+    pub daylight_bias: i32,
+    /// Whether daylight saving time is in effect, `None` if the zone has no transitions.
+    pub is_dst: Option<bool>,
+    /// Whether automatic adjustment for daylight saving time is disabled by the user.
+    pub dynamic_daylight_time_disabled: bool,
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+impl WindowsSystemTzInfo {
+    #[must_use]
+    /// Tries to get the details of the current Windows timezone.
+    pub fn get() -> Option<Self> {
+        Self::try_get().ok()
+    }
+
+    /// Gets the details of the current Windows timezone.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DynamicTimeZoneInformation`] with the `GetLastError` code on failure.
+    pub fn try_get() -> Result<Self, Error> {
+        use ::windows::Win32::{
+            Foundation::GetLastError,
+            System::Time::{GetDynamicTimeZoneInformation, DYNAMIC_TIME_ZONE_INFORMATION},
+        };
+
+        // Reference: https://learn.microsoft.com/en-us/windows/win32/api/timezoneapi/nf-timezoneapi-getdynamictimezoneinformation
+        let mut zone_info = DYNAMIC_TIME_ZONE_INFORMATION::default();
+        let is_dst =
+            match unsafe { GetDynamicTimeZoneInformation(::std::ptr::addr_of_mut!(zone_info)) } {
+                // `TIME_ZONE_ID_UNKNOWN`, `TIME_ZONE_ID_STANDARD` and `TIME_ZONE_ID_DAYLIGHT`
+                0 => None,
+                1 => Some(false),
+                2 => Some(true),
+                _ => {
+                    return Err(Error::DynamicTimeZoneInformation(
+                        unsafe { GetLastError() }.0,
+                    ))
+                }
+            };
+
+        Ok(Self {
+            key_name: zone_info.TimeZoneKeyName.as_utf8().unwrap_or_default(),
+            bias: zone_info.Bias,
+            standard_name: zone_info.StandardName.as_utf8().unwrap_or_default(),
+            standard_bias: zone_info.StandardBias,
+            daylight_name: zone_info.DaylightName.as_utf8().unwrap_or_default(),
+            daylight_bias: zone_info.DaylightBias,
+            is_dst,
+            dynamic_daylight_time_disabled: zone_info.DynamicDaylightTimeDisabled.0 != 0,
+        })
+    }
+
+    #[must_use]
+    /// Returns the current offset from UTC.
+    pub fn offset(&self) -> Option<chrono::FixedOffset> {
+        let bias = self.bias
+            + if self.is_dst == Some(true) {
+                self.daylight_bias
+            } else {
+                self.standard_bias
+            };
+        chrono::FixedOffset::west_opt(bias.checked_mul(60)?)
+    }
+
+    #[must_use]
+    /// Returns the `WindowsTz` of the `key_name` (see [`WindowsTz::get_for_user()`]).
+    pub fn windows_tz(&self) -> Option<&'static WindowsTz> {
+        WindowsTz::get_for_user(&self.key_name)
+    }
+
+    #[must_use]
+    /// Returns the daylight saving time rules of the `key_name` zone for the `year`,
+    /// as returned by `GetTimeZoneInformationForYear` (e.g. to cross-check historical data).
+    pub fn rules_for_year(&self, year: u16) -> Option<WindowsYearRules> {
+        use ::windows::Win32::System::Time::{
+            GetTimeZoneInformationForYear, DYNAMIC_TIME_ZONE_INFORMATION, TIME_ZONE_INFORMATION,
+        };
+
+        let mut zone_info = DYNAMIC_TIME_ZONE_INFORMATION::default();
+        let len = zone_info.TimeZoneKeyName.len() - 1;
+        for (x, y) in zone_info
+            .TimeZoneKeyName
+            .iter_mut()
+            .zip(self.key_name.encode_utf16().take(len))
+        {
+            *x = y;
+        }
+        zone_info.DynamicDaylightTimeDisabled.0 = self.dynamic_daylight_time_disabled.into();
+
+        // Reference: https://learn.microsoft.com/en-us/windows/win32/api/timezoneapi/nf-timezoneapi-gettimezoneinformationforyear
+        let mut rules = TIME_ZONE_INFORMATION::default();
+        if !unsafe {
+            GetTimeZoneInformationForYear(
+                year,
+                Some(::std::ptr::addr_of!(zone_info)),
+                ::std::ptr::addr_of_mut!(rules),
+            )
+        }
+        .as_bool()
+        {
+            return None;
+        }
+
+        Some(WindowsYearRules {
+            year,
+            bias: rules.Bias,
+            standard_bias: rules.StandardBias,
+            daylight_bias: rules.DaylightBias,
+            standard_start: transition(year, rules.StandardDate),
+            daylight_start: transition(year, rules.DaylightDate),
+        })
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Resolves a transition date of `TIME_ZONE_INFORMATION` in the `year`, which is either
+/// absolute or (when `wYear` is `0`) the `wDay`-th `wDayOfWeek` of `wMonth`, `5` being the last.
+fn transition(
+    year: u16,
+    date: ::windows::Win32::Foundation::SYSTEMTIME,
+) -> Option<chrono::NaiveDateTime> {
+    use chrono::{NaiveDate, NaiveTime, Weekday};
+
+    // No transition at all
+    if date.wMonth == 0 {
+        return None;
+    }
+
+    let month = u32::from(date.wMonth);
+    let day = if date.wYear == 0 {
+        // `wDayOfWeek` starts on Sunday
+        let weekday = Weekday::try_from(u8::try_from((date.wDayOfWeek % 7 + 6) % 7).ok()?).ok()?;
+        let week = u8::try_from(date.wDay).ok()?;
+        (1..=week).rev().find_map(|week| {
+            NaiveDate::from_weekday_of_month_opt(i32::from(year), month, weekday, week)
+        })?
+    } else {
+        NaiveDate::from_ymd_opt(i32::from(date.wYear), month, u32::from(date.wDay))?
+    };
+
+    let time = NaiveTime::from_hms_milli_opt(
+        u32::from(date.wHour),
+        u32::from(date.wMinute),
+        u32::from(date.wSecond),
+        u32::from(date.wMilliseconds),
+    )?;
+
+    Some(day.and_time(time))
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Daylight saving time rules of a Windows timezone for a given year
+/// (see [`WindowsSystemTzInfo::rules_for_year()`]).
+///
+/// Biases are in minutes, such that `UTC = local time + bias`.
+pub struct WindowsYearRules {
+    /// Year of the rules.
+    pub year: u16,
+    /// Bias, not including the standard or daylight bias.
+    pub bias: i32,
+    /// Bias added to `bias` during standard time.
+    pub standard_bias: i32,
+    /// Bias added to `bias` during daylight saving time.
+    pub daylight_bias: i32,
+    /// Local date and time of the transition to standard time, if any.
+    pub standard_start: Option<chrono::NaiveDateTime>,
+    /// Local date and time of the transition to daylight saving time, if any.
+    pub daylight_start: Option<chrono::NaiveDateTime>,
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+impl WindowsYearRules {
+    #[must_use]
+    /// Returns the offset from UTC during standard time.
+    pub fn standard_offset(&self) -> Option<chrono::FixedOffset> {
+        chrono::FixedOffset::west_opt((self.bias + self.standard_bias).checked_mul(60)?)
+    }
+
+    #[must_use]
+    /// Returns the offset from UTC during daylight saving time, if the year has any.
+    pub fn daylight_offset(&self) -> Option<chrono::FixedOffset> {
+        self.daylight_start?;
+        chrono::FixedOffset::west_opt((self.bias + self.daylight_bias).checked_mul(60)?)
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Windows probe used to detect the system timezone (see [`system_tz_with_probe()`]).
+pub enum WindowsProbe {
+    /// Timezone of the unix host when running under Wine, whose Windows zone is often generic.
+    Wine,
+    /// `Windows.Globalization.Calendar` (`WinRT`), which is unavailable on Server Core and Nano Server.
+    Calendar,
+    /// `GetDynamicTimeZoneInformation` (Win32).
+    DynamicTimeZoneInformation,
+    /// `TimeZoneKeyName` value of the registry.
+    Registry,
+    /// `GetTimeZoneInformation` standard name, looked up in the registry.
+    StandardName,
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Scope of a Windows timezone setting, which may diverge in multi-session environments
+/// (e.g. Remote Desktop Services with time zone redirection, or VDI).
+pub enum WindowsTzScope {
+    /// Timezone of the current user session.
+    User,
+    /// Machine-wide timezone (`HKEY_LOCAL_MACHINE` registry).
+    Machine,
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+impl WindowsProbe {
+    /// All the probes, in order of precedence.
+    pub const ALL: [Self; 5] = [
+        Self::Wine,
+        Self::Calendar,
+        Self::DynamicTimeZoneInformation,
+        Self::Registry,
+        Self::StandardName,
+    ];
+
+    #[must_use]
+    /// Returns the scope of the setting read by this probe.
+    pub const fn scope(self) -> WindowsTzScope {
+        match self {
+            Self::Registry => WindowsTzScope::Machine,
+            Self::Wine | Self::Calendar | Self::DynamicTimeZoneInformation | Self::StandardName => {
+                WindowsTzScope::User
+            }
+        }
+    }
+
+    #[must_use]
+    /// Tries to get the system timezone with this probe only.
+    pub fn probe(self) -> Option<Tz> {
+        self.try_probe().ok()
+    }
+
+    /// Gets the system timezone with this probe only.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Calendar`] or [`Error::DynamicTimeZoneInformation`] if the underlying
+    /// API fails, or [`Error::UnknownTimezone`] if the probe doesn't find a known timezone.
+    pub fn try_probe(self) -> Result<Tz, Error> {
+        self.read_raw().1
+    }
+
+    /// Reads the raw value of this probe (e.g. the Windows zone name) and maps it to a [`Tz`].
+    fn read_raw(self) -> (Option<String>, Result<Tz, Error>) {
+        use ::windows::{
+            Globalization::Calendar,
+            Win32::System::Time::{GetTimeZoneInformation, TIME_ZONE_INFORMATION},
+        };
+
+        let raw = match self {
+            Self::Wine => {
+                let tz = is_wine().then(wine_host_tz).flatten();
+                return (None, tz.ok_or(Error::UnknownTimezone));
+            }
+            // Activation fails where WinRT is missing (e.g. Server Core), falling through to Win32
+            Self::Calendar => match Calendar::new().and_then(|cal| cal.GetTimeZone()) {
+                Ok(zone) => {
+                    let zone = zone.to_string_lossy();
+                    let tz = zone.parse_tz().ok_or_else(|| Error::unparsed(&zone));
+                    return (Some(zone), tz);
+                }
+                Err(err) => return (None, Err(Error::Calendar(err.code().0))),
+            },
+            Self::DynamicTimeZoneInformation => match WindowsSystemTzInfo::try_get() {
+                Ok(info) => Some(info.key_name).filter(|zone| !zone.is_empty()),
+                Err(err) => return (None, Err(err)),
+            },
+            // Packaged (UWP) apps run in an AppContainer where the registry is restricted,
+            // leaving them with the above WinRT and time zone APIs only
+            Self::Registry | Self::StandardName if is_app_container() => None,
+            Self::Registry => registry_string(
+                ::windows::w!(r"SYSTEM\CurrentControlSet\Control\TimeZoneInformation"),
+                ::windows::w!("TimeZoneKeyName"),
+            ),
+            Self::StandardName => {
+                // Legacy: `TimeZoneKeyName` may be empty on older versions of Windows
+                // Reference: https://learn.microsoft.com/en-us/windows/win32/api/timezoneapi/nf-timezoneapi-gettimezoneinformation
+                let mut zone_info = TIME_ZONE_INFORMATION::default();
+                if let 0..=2 =
+                    unsafe { GetTimeZoneInformation(::std::ptr::addr_of_mut!(zone_info)) }
+                {
+                    zone_info.StandardName.as_utf8().map(|name| {
+                        registry_zone_by(|key| {
+                            registry_string(key, ::windows::w!("Std")).as_deref()
+                                == Some(name.as_str())
+                        })
+                        .unwrap_or(name)
+                    })
+                } else {
+                    None
+                }
+            }
+        };
+
+        let tz = raw.as_deref().map_or(Err(Error::UnknownTimezone), |zone| {
+            windows_zone_tz(zone).ok_or_else(|| Error::unparsed(zone))
+        });
+        (raw, tz)
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Returns the system timezone along with the probe which detected it, trying each of
+/// [`WindowsProbe::ALL`] in turn so that missing APIs (e.g. `WinRT` on Server Core)
+/// degrade to the next one.
+pub fn system_tz_with_probe() -> Option<(Tz, WindowsProbe)> {
+    WindowsProbe::ALL
+        .into_iter()
+        .find_map(|probe| probe.probe().map(|tz| (tz, probe)))
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+impl From<WindowsProbe> for DetectionSource {
+    fn from(probe: WindowsProbe) -> Self {
+        match probe {
+            WindowsProbe::Wine => Self::WindowsWine,
+            WindowsProbe::Calendar => Self::WindowsCalendar,
+            WindowsProbe::DynamicTimeZoneInformation => Self::WindowsDynamicTimeZoneInformation,
+            WindowsProbe::Registry => Self::WindowsRegistry,
+            WindowsProbe::StandardName => Self::WindowsStandardName,
+        }
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+impl TzProvider for WindowsProbe {
+    fn source(&self) -> DetectionSource {
+        (*self).into()
+    }
+
+    fn read(&self) -> Reading {
+        let (raw, tz) = self.read_raw();
+        Reading { raw, tz }
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+impl ProviderChain {
+    /// Returns the built-in providers on Windows: each of [`WindowsProbe::ALL`] in turn.
+    fn platform() -> Self {
+        let mut chain = Self::new();
+        for probe in WindowsProbe::ALL {
+            chain.push(probe);
+        }
+        chain
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Returns the system timezone in the given `scope` along with the probe which detected it,
+/// trying only the probes of [`WindowsProbe::ALL`] reading that scope.
+pub fn system_tz_with_scope(scope: WindowsTzScope) -> Option<(Tz, WindowsProbe)> {
+    WindowsProbe::ALL
+        .into_iter()
+        .filter(|probe| probe.scope() == scope)
+        .find_map(|probe| probe.probe().map(|tz| (tz, probe)))
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+/// Handle of a background thread watching the Windows timezone (see [`watch()`]),
+/// which is stopped when dropped.
+pub struct Watcher {
+    stop: ::windows::Win32::Foundation::HANDLE,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        use ::windows::Win32::{Foundation::CloseHandle, System::Threading::SetEvent};
+
+        unsafe { SetEvent(self.stop) };
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        unsafe { CloseHandle(self.stop) };
+    }
+}
+
+#[cfg(all(feature = "std", target_family = "windows"))]
+#[must_use]
+/// Watches the `TimeZoneInformation` registry key in a background thread,
+/// calling `callback` with the new system timezone whenever the user changes it.
+///
+/// As [`SystemTz::system_tz()`] never caches its result, it reflects the change right away.
+///
+/// Returns `None` if the key can't be watched (e.g. in an app container)
+/// or the thread can't be spawned.
+pub fn watch<F>(mut callback: F) -> Option<Watcher>
+where
+    F: FnMut(Option<Tz>) + Send + 'static,
+{
+    use ::windows::Win32::{
+        Foundation::{CloseHandle, ERROR_SUCCESS, WAIT_OBJECT_0},
+        System::{
+            Registry::{
+                RegCloseKey, RegNotifyChangeKeyValue, RegOpenKeyExW, HKEY, HKEY_LOCAL_MACHINE,
+                KEY_NOTIFY, KEY_WOW64_64KEY, REG_NOTIFY_CHANGE_LAST_SET,
+            },
+            Threading::{CreateEventW, WaitForMultipleObjects, INFINITE},
+        },
+    };
+
+    let mut key = HKEY::default();
+    // Reference: https://learn.microsoft.com/en-us/windows/win32/api/winreg/nf-winreg-regopenkeyexw
+    let status = unsafe {
+        RegOpenKeyExW(
+            HKEY_LOCAL_MACHINE,
+            ::windows::w!(r"SYSTEM\CurrentControlSet\Control\TimeZoneInformation"),
+            0,
+            KEY_NOTIFY | KEY_WOW64_64KEY,
+            ::std::ptr::addr_of_mut!(key),
+        )
+    };
+    if status != ERROR_SUCCESS {
+        return None;
+    }
+
+    // Reference: https://learn.microsoft.com/en-us/windows/win32/api/synchapi/nf-synchapi-createeventw
+    let events = unsafe {
+        CreateEventW(None, true, false, None).and_then(|stop| {
+            match CreateEventW(None, false, false, None) {
+                Ok(change) => Ok((stop, change)),
+                Err(err) => {
+                    CloseHandle(stop);
+                    Err(err)
+                }
+            }
+        })
+    };
+    let Ok((stop, change)) = events else {
+        unsafe { RegCloseKey(key) };
+        return None;
+    };
+
+    let thread = std::thread::Builder::new()
+        .name("system_tz-watch".into())
+        .spawn(move || {
+            let mut last = Tz::system_tz();
+            loop {
+                // Reference: https://learn.microsoft.com/en-us/windows/win32/api/winreg/nf-winreg-regnotifychangekeyvalue
+                let status = unsafe {
+                    RegNotifyChangeKeyValue(key, false, REG_NOTIFY_CHANGE_LAST_SET, change, true)
+                };
+                if status != ERROR_SUCCESS
+                    || unsafe { WaitForMultipleObjects(&[change, stop], false, INFINITE) }
+                        != WAIT_OBJECT_0
+                {
+                    break;
+                }
+
+                let tz = Tz::system_tz();
+                if tz != last {
+                    last = tz;
+                    callback(tz);
+                }
+            }
+
+            unsafe {
+                CloseHandle(change);
+                RegCloseKey(key);
+            }
+        });
+    let Ok(thread) = thread else {
+        unsafe {
+            CloseHandle(stop);
+            CloseHandle(change);
+            RegCloseKey(key);
+        }
+        return None;
+    };
+
+    Some(Watcher {
+        stop,
+        thread: Some(thread),
+    })
+}
+
+// LOCALIZED NAMES /////////////////////////////////////////////////////////////
+
+#[cfg(feature = "localized-names")]
+include!(concat!(env!("OUT_DIR"), "/metazones.rs"));
+
+#[cfg(feature = "localized-names")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Long localized names of a timezone, from the CLDR metazone it currently belongs to.
+pub struct TzNames {
+    /// Generic name (e.g. `Mitteleuropäische Zeit`).
+    pub generic: Option<&'static str>,
+    /// Name of the standard time (e.g. `Mitteleuropäische Normalzeit`).
+    pub standard: Option<&'static str>,
+    /// Name of the daylight saving time (e.g. `Mitteleuropäische Sommerzeit`).
+    pub daylight: Option<&'static str>,
+}
+
+#[cfg(feature = "localized-names")]
+/// Localized names of timezones, based on bundled CLDR data.
+///
+/// Note that the bundled data is a subset of CLDR, covering the most common
+/// timezones in English (`en`), German (`de`) and French (`fr`).
+pub trait LocalizedTz {
+    #[must_use]
+    /// Returns the CLDR metazone the timezone currently belongs to (e.g. `Europe_Central`).
+    fn metazone(&self) -> Option<&'static str>;
+
+    #[must_use]
+    /// Returns the long names of the timezone in the `locale` (e.g. `de-AT`),
+    /// falling back to its language (e.g. `de`).
+    fn localized_name(&self, locale: &str) -> Option<TzNames>;
+}
+
+#[cfg(feature = "localized-names")]
+impl LocalizedTz for Tz {
+    fn metazone(&self) -> Option<&'static str> {
+        METAZONES
+            .binary_search_by(|(iana, _)| (*iana).cmp(self.name()))
+            .ok()
+            .map(|index| METAZONES[index].1)
+    }
+
+    fn localized_name(&self, locale: &str) -> Option<TzNames> {
+        let metazone = self.metazone()?;
+        let locale = locale.replace('-', "_");
+        let language = locale.split('_').next().unwrap_or_default();
+
+        let find = |locale: &str| {
+            METAZONE_NAMES
+                .binary_search_by(|(x, y, _)| str::cmp(x, locale).then_with(|| y.cmp(&metazone)))
+                .ok()
+                .map(|index| METAZONE_NAMES[index].2)
+        };
+
+        find(&locale).or_else(|| find(language))
+    }
+}
+
+#[cfg(feature = "localized-names")]
+#[must_use]
+/// Returns a name of `tz` to display in the `locale` (e.g. `heure d’Europe centrale`
+/// for `Europe/Paris` in `fr`), i.e. its generic name or else its standard one
+/// (see [`LocalizedTz::localized_name()`]).
+///
+/// ```
+/// let name = system_tz::display_name(chrono_tz::Europe::Paris, "fr-FR");
+/// assert_eq!(name, Some("heure d’Europe centrale"));
+/// ```
+pub fn display_name(tz: Tz, locale: &str) -> Option<&'static str> {
+    tz.localized_name(locale)
+        .and_then(|names| names.generic.or(names.standard))
+}
+
+// C API ///////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "capi")]
+/// C API, declared in the `include/system_tz.h` header generated with `cbindgen`.
+pub mod capi {
+    use std::ffi::{c_char, CString};
+
+    #[no_mangle]
+    #[must_use]
+    /// Returns the IANA name of the system timezone (e.g. `Europe/Paris`),
+    /// or `NULL` if it can't be detected.
+    ///
+    /// The string must be released with `system_tz_free()`.
+    pub extern "C" fn system_tz_name() -> *mut c_char {
+        super::system_tz_name()
+            .and_then(|name| CString::new(name).ok())
+            .map_or(core::ptr::null_mut(), CString::into_raw)
+    }
+
+    #[no_mangle]
+    /// Releases a string returned by `system_tz_name()`.
+    ///
+    /// # Safety
+    ///
+    /// `name` must be `NULL` or a string returned by `system_tz_name()` not released yet.
+    pub unsafe extern "C" fn system_tz_free(name: *mut c_char) {
+        if !name.is_null() {
+            drop(unsafe { CString::from_raw(name) });
+        }
+    }
+}
+
+#[cfg(feature = "uniffi")]
+/// Kotlin and Swift API generated with `UniFFI` (see the `uniffi-bindgen` binary),
+/// where timezones are represented by their IANA names.
+// `UniFFI` can't lift optional strings as references
+#[allow(clippy::needless_pass_by_value)]
+pub mod uniffi_api {
+    use super::{ParseTz, WindowsTz};
+
+    #[uniffi::export(name = "system_tz")]
+    #[must_use]
+    /// Returns the IANA name of the system timezone (e.g. `Europe/Paris`), if detected.
+    pub fn system_tz_name() -> Option<String> {
+        super::system_tz_name()
+    }
+
+    #[uniffi::export]
+    #[must_use]
+    /// Returns the territory of the current user (see [`super::system_territory()`]).
+    pub fn system_territory() -> Option<String> {
+        super::system_territory()
+    }
+
+    #[uniffi::export]
+    #[must_use]
+    /// Proposes the IANA names of the timezones currently at `offset_seconds` from UTC,
+    /// optionally in a `territory` (see [`super::guess_tz()`]).
+    pub fn guess_tz(offset_seconds: i32, territory: Option<String>) -> Vec<String> {
+        chrono::FixedOffset::east_opt(offset_seconds)
+            .map(|offset| super::guess_tz(offset, territory.as_deref()))
+            .unwrap_or_default()
+            .iter()
+            .map(|tz| tz.name().to_owned())
+            .collect()
+    }
+
+    #[uniffi::export]
+    #[must_use]
+    /// Returns the preferred IANA name of the Windows `zone` (e.g. `Romance Standard Time`),
+    /// optionally in a `territory` (see [`WindowsTz::get()`]).
+    pub fn windows_to_iana(zone: &str, territory: Option<String>) -> Option<String> {
+        let windows = WindowsTz::get(zone, territory.as_deref())?;
+        windows.iana().first().map(|name| (*name).to_owned())
+    }
+
+    #[uniffi::export]
+    #[must_use]
+    /// Returns the Windows zone of the IANA timezone `name` (see [`WindowsTz::from_iana()`]).
+    pub fn iana_to_windows(name: &str) -> Option<String> {
+        let tz = name.parse_tz()?;
+        let windows = WindowsTz::from_iana(&tz);
+        windows.first().map(|windows| windows.zone().to_string())
+    }
+}
+
+// NODE-API ////////////////////////////////////////////////////////////////////
+
+#[cfg(all(
+    feature = "napi",
+    any(target_family = "unix", target_family = "windows")
+))]
+/// Node-API addon exporting `systemTz()`, which detects the timezone natively
+/// rather than with `Intl` (e.g. reading `/etc/localtime` or the registry).
+///
+/// The functions of Node-API are looked up in the process when the addon is loaded
+/// rather than linked, so that the executables linking the crate (e.g. the `tz` binary)
+/// don't depend on Node.js.
+///
+/// Reference: <https://nodejs.org/api/n-api.html>
+#[warn(clippy::undocumented_unsafe_blocks)]
+mod napi {
+    use core::ffi::{c_char, c_void, CStr};
+
+    type Env = *mut c_void;
+    type Value = *mut c_void;
+    type Status = i32;
+    type Callback = unsafe extern "C" fn(Env, *mut c_void) -> Value;
+    type CreateFunction = unsafe extern "C" fn(
+        Env,
+        *const c_char,
+        usize,
+        Callback,
+        *mut c_void,
+        *mut Value,
+    ) -> Status;
+    type CreateStringUtf8 = unsafe extern "C" fn(Env, *const c_char, usize, *mut Value) -> Status;
+    type SetNamedProperty = unsafe extern "C" fn(Env, Value, *const c_char, Value) -> Status;
+
+    /// Returns the address of the Node-API function `name` in the process.
+    fn symbol(name: &CStr) -> Option<*mut c_void> {
+        #[cfg(target_family = "unix")]
+        {
+            // SAFETY: `name` is a valid C string, and `RTLD_DEFAULT` searches the global scope
+            // of the process (including `node`) without requiring a handle
+            let address = unsafe { libc::dlsym(libc::RTLD_DEFAULT, name.as_ptr()) };
+            (!address.is_null()).then_some(address)
+        }
+        #[cfg(target_family = "windows")]
+        {
+            use ::windows::{
+                core::{PCSTR, PCWSTR},
+                Win32::System::LibraryLoader::{GetModuleHandleW, GetProcAddress},
+            };
+
+            // SAFETY: a `NULL` module name returns the handle of the executable (`node.exe`),
+            // which stays loaded for the lifetime of the process
+            let process = unsafe { GetModuleHandleW(PCWSTR::null()) }.ok()?;
+            // SAFETY: `process` is a valid module handle and `name` a valid C string
+            unsafe { GetProcAddress(process, PCSTR(name.as_ptr().cast())) }
+                .map(|address| address as *mut c_void)
+        }
+    }
+
+    /// Functions of Node-API used by the addon.
+    pub struct Api {
+        create_function: CreateFunction,
+        create_string_utf8: CreateStringUtf8,
+        set_named_property: SetNamedProperty,
+    }
+
+    impl Api {
+        /// Looks up the functions in the process, returning `None` outside of Node.js.
+        pub fn get() -> Option<Self> {
+            use core::mem::transmute;
+
+            // SAFETY: the symbols are functions of Node-API, whose signatures are declared
+            // above as in `node_api.h`, and functions share the representation of pointers
+            unsafe {
+                Some(Self {
+                    create_function: transmute::<*mut c_void, CreateFunction>(symbol(
+                        c"napi_create_function",
+                    )?),
+                    create_string_utf8: transmute::<*mut c_void, CreateStringUtf8>(symbol(
+                        c"napi_create_string_utf8",
+                    )?),
+                    set_named_property: transmute::<*mut c_void, SetNamedProperty>(symbol(
+                        c"napi_set_named_property",
+                    )?),
+                })
+            }
+        }
+    }
+
+    /// `systemTz()`: returns the IANA name of the system timezone (`undefined` if not detected).
+    unsafe extern "C" fn system_tz(env: Env, _: *mut c_void) -> Value {
+        let mut value = core::ptr::null_mut();
+        if let (Some(api), Some(name)) = (Api::get(), super::system_tz_name()) {
+            let name_ptr = name.as_ptr().cast();
+            // SAFETY: `env` is the environment of the current call, and `name_ptr` points
+            // to `name.len()` bytes of UTF-8 which are copied into the JavaScript string
+            unsafe { (api.create_string_utf8)(env, name_ptr, name.len(), &raw mut value) };
+        }
+        // A `NULL` value is `undefined` to JavaScript
+        value
+    }
+
+    #[no_mangle]
+    /// Entry point of the addon, adding `systemTz()` to its `exports`.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called by Node.js when loading the addon.
+    pub unsafe extern "C" fn napi_register_module_v1(env: Env, exports: Value) -> Value {
+        if let Some(api) = Api::get() {
+            let name = c"systemTz".as_ptr();
+            let data = core::ptr::null_mut();
+            let mut function = core::ptr::null_mut();
+            // SAFETY: `env` and `exports` are handed out by Node.js for this call, `name` is
+            // a NUL-terminated string (hence the `NAPI_AUTO_LENGTH` length, i.e. `usize::MAX`)
+            // and `system_tz` has the signature of a `napi_callback`
+            unsafe {
+                if (api.create_function)(env, name, usize::MAX, system_tz, data, &raw mut function)
+                    == 0
+                {
+                    (api.set_named_property)(env, exports, name, function);
+                }
+            }
+        }
+        exports
+    }
+}
+
+// WASM ////////////////////////////////////////////////////////////////////////
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+#[must_use]
+/// Tries to get the timezone resolved by the JavaScript `Intl` API of the host.
+fn js_tz() -> Option<Tz> {
+    js_intl_name().and_then(|name| name.parse_tz())
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+#[must_use]
+/// Tries to get the name of the timezone resolved by the JavaScript `Intl` API of the host,
+/// preferring the non-standard `timeZoneName` if it parses.
+fn js_intl_name() -> Option<String> {
+    use {js_sys::Intl::DateTimeFormat, js_sys::Reflect};
+
+    // Some engines (e.g. older Hermes in React Native) lack `Intl.DateTimeFormat`,
+    // whose construction would then throw
+    let intl = Reflect::get(&js_sys::global(), &"Intl".into()).ok()?;
+    if !Reflect::get(&intl, &"DateTimeFormat".into())
+        .ok()?
+        .is_function()
+    {
+        return None;
+    }
+
+    // Reference: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/DateTimeFormat
+    let opts = DateTimeFormat::default().resolved_options();
+    let name = |key: &str| Reflect::get(&opts, &key.into()).ok()?.as_string();
+    name("timeZoneName")
+        .filter(|name| name.parse_tz().is_some())
+        .or_else(|| name("timeZone"))
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+#[must_use]
+/// Tries to get the territory of the locale resolved by the JavaScript `Intl` API of the host.
+fn js_territory() -> Option<String> {
+    use {js_sys::Intl::DateTimeFormat, js_sys::Reflect};
+
+    let intl = Reflect::get(&js_sys::global(), &"Intl".into()).ok()?;
+    if !Reflect::get(&intl, &"DateTimeFormat".into())
+        .ok()?
+        .is_function()
+    {
+        return None;
+    }
+
+    let opts = DateTimeFormat::default().resolved_options();
+    Reflect::get(&opts, &"locale".into())
+        .ok()?
+        .as_string()
+        .and_then(|locale| locale_territory(&locale))
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+#[must_use]
+/// Tries to read `process.env.TZ` on Node.js hosts (e.g. Electron main process).
+fn js_process_env() -> Option<String> {
+    use js_sys::Reflect;
+
+    let process = Reflect::get(&js_sys::global(), &"process".into()).ok()?;
+    let env = Reflect::get(&process, &"env".into()).ok()?;
+    Reflect::get(&env, &"TZ".into()).ok()?.as_string()
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+/// Name of the global function which hosts lacking `Intl` can define to bridge
+/// a native call returning the IANA name of the timezone (see [`JsProbe::NativeBridge`]).
+pub const JS_NATIVE_BRIDGE: &str = "__systemTz";
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+#[must_use]
+/// Tries to map the offsets of the JavaScript `Date` across the current year to the matching
+/// `Etc/GMT` timezone if constant, or else to the single timezone observing the same offsets.
+fn js_date_offset_tz() -> Option<Tz> {
+    use {chrono::Offset, chrono::TimeZone, js_sys::Date};
+
+    let year = Date::new_0().get_utc_full_year();
+    // `getTimezoneOffset()` on the first day of each month, in minutes west of UTC
+    let samples = (0..12)
+        .map(|month| {
+            let date = chrono::NaiveDate::from_ymd_opt(i32::try_from(year).ok()?, month + 1, 1)?;
+            let millis = Date::utc(f64::from(year), f64::from(month));
+            Some((date, Date::new(&millis.into()).get_timezone_offset()))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let (_, first) = samples.first()?;
+    if samples
+        .iter()
+        .all(|(_, offset)| (offset - first).abs() < 1.0)
+    {
+        // Like the inverted sign of `Etc/GMT` zones, `getTimezoneOffset()` is positive west of UTC
+        let hours = first / 60.0;
+        return if hours.fract() == 0.0 {
+            format!("Etc/GMT{hours:+}").parse().ok()
+        } else {
+            None
+        };
+    }
+
+    let mut candidates = chrono_tz::TZ_VARIANTS.iter().filter(|tz| {
+        samples.iter().all(|(date, offset)| {
+            let seconds = tz.offset_from_utc_date(date).fix().local_minus_utc();
+            (f64::from(seconds) + offset * 60.0).abs() < 1.0
+        })
+    });
+    match (candidates.next(), candidates.next()) {
+        (Some(tz), None) => Some(*tz),
+        _ => None,
+    }
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+#[must_use]
+/// Returns `true` when running in Node.js (`process.versions.node` is defined).
+fn is_node() -> bool {
+    use js_sys::Reflect;
+
+    Reflect::get(&js_sys::global(), &"process".into())
+        .and_then(|process| Reflect::get(&process, &"versions".into()))
+        .and_then(|versions| Reflect::get(&versions, &"node".into()))
+        .is_ok_and(|node| node.is_string())
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+#[must_use]
+/// Returns `true` when running in an [Electron](https://www.electronjs.org/) process
+/// (`process.versions.electron` is defined).
+pub fn is_electron() -> bool {
+    use js_sys::Reflect;
+
+    Reflect::get(&js_sys::global(), &"process".into())
+        .and_then(|process| Reflect::get(&process, &"versions".into()))
+        .and_then(|versions| Reflect::get(&versions, &"electron".into()))
+        .is_ok_and(|electron| electron.is_string())
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Sources of the timezone on JavaScript hosts.
+///
+/// In the Electron main process both are available and can disagree, as `Intl` doesn't
+/// follow changes of `process.env.TZ` made at runtime. Renderer processes only see `Intl`,
+/// hence its precedence in [`JsProbe::ALL`]: apps changing `process.env.TZ` should rather
+/// use [`JsPrecedence::EnvFirst`] (see [`set_js_precedence()`]).
+pub enum JsProbe {
+    /// `Intl.DateTimeFormat().resolvedOptions().timeZone`.
+    Intl,
+    /// `process.env.TZ` (Node.js, Electron main process).
+    ProcessEnv,
+    /// Global function named [`JS_NATIVE_BRIDGE`], e.g. bridged to a native module
+    /// by React Native apps running on Hermes.
+    NativeBridge,
+    /// Offsets of `Date` across the current year, mapped to an `Etc/GMT` timezone when constant
+    /// (whole hours only), or else to the single timezone observing the same offsets.
+    ///
+    /// This is **heuristic** (see [`JsProbe::is_heuristic()`]), e.g. for webviews
+    /// whose `Intl` resolves no timezone.
+    DateOffset,
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+impl From<JsProbe> for DetectionSource {
+    fn from(probe: JsProbe) -> Self {
+        match probe {
+            JsProbe::Intl => Self::JsIntl,
+            JsProbe::ProcessEnv => Self::JsProcessEnv,
+            JsProbe::NativeBridge => Self::JsNativeBridge,
+            JsProbe::DateOffset => Self::JsDateOffset,
+        }
+    }
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+impl TzProvider for JsProbe {
+    fn source(&self) -> DetectionSource {
+        (*self).into()
+    }
+
+    fn read(&self) -> Reading {
+        let (raw, tz) = self.read_raw();
+        Reading { raw, tz }
+    }
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+impl JsProbe {
+    /// All the probes, in default order of precedence.
+    pub const ALL: [Self; 4] = [
+        Self::Intl,
+        Self::ProcessEnv,
+        Self::NativeBridge,
+        Self::DateOffset,
+    ];
+
+    #[must_use]
+    /// Returns `true` if the timezone detected by this probe is a guess.
+    pub const fn is_heuristic(self) -> bool {
+        matches!(self, Self::DateOffset)
+    }
+
+    #[must_use]
+    /// Tries to get the system timezone with this probe only.
+    pub fn probe(self) -> Option<Tz> {
+        self.read_raw().1.ok()
+    }
+
+    /// Reads the raw value of this probe (e.g. the name resolved by `Intl`) and maps it
+    /// to a [`Tz`].
+    fn read_raw(self) -> (Option<String>, Result<Tz, Error>) {
+        let raw = match self {
+            Self::Intl => js_intl_name(),
+            Self::ProcessEnv => js_process_env(),
+            Self::NativeBridge => {
+                js_call(&js_sys::global(), JS_NATIVE_BRIDGE, &[]).and_then(|tz| tz.as_string())
+            }
+            Self::DateOffset => return (None, js_date_offset_tz().ok_or(Error::UnknownTimezone)),
+        };
+        let tz = raw.as_deref().map_or(Err(Error::UnknownTimezone), |raw| {
+            raw.parse_tz().ok_or_else(|| Error::unparsed(raw))
+        });
+        (raw, tz)
+    }
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// Order in which [`try_system_tz()`] tries `Intl` and `process.env.TZ` (see [`JsProbe`]).
+pub enum JsPrecedence {
+    /// `process.env.TZ` first on Node.js, whose `Intl` may be compiled without ICU data,
+    /// and `Intl` first elsewhere (including Electron).
+    #[default]
+    Auto,
+    /// `Intl` first, as in [`JsProbe::ALL`].
+    IntlFirst,
+    /// `process.env.TZ` first.
+    EnvFirst,
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+impl JsPrecedence {
+    #[must_use]
+    /// Returns the probes in this order of precedence.
+    pub fn probes(self) -> [JsProbe; 4] {
+        let env_first = match self {
+            Self::Auto => is_node() && !is_electron(),
+            Self::IntlFirst => false,
+            Self::EnvFirst => true,
+        };
+
+        let mut probes = JsProbe::ALL;
+        if env_first {
+            probes.swap(0, 1);
+        }
+        probes
+    }
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+/// Current [`JsPrecedence`], as its index in declaration order.
+static JS_PRECEDENCE: std::sync::atomic::AtomicU8 = std::sync::atomic::AtomicU8::new(0);
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+/// Sets the order in which [`try_system_tz()`] tries the JavaScript probes.
+pub fn set_js_precedence(precedence: JsPrecedence) {
+    JS_PRECEDENCE.store(precedence as u8, std::sync::atomic::Ordering::Relaxed);
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+#[must_use]
+/// Returns the order in which [`try_system_tz()`] tries the JavaScript probes.
+pub fn js_precedence() -> JsPrecedence {
+    match JS_PRECEDENCE.load(std::sync::atomic::Ordering::Relaxed) {
+        1 => JsPrecedence::IntlFirst,
+        2 => JsPrecedence::EnvFirst,
+        _ => JsPrecedence::Auto,
+    }
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+#[must_use]
+/// Returns the system timezone along with the probe which detected it,
+/// trying each of `probes` in turn.
+pub fn system_tz_with_probes(probes: &[JsProbe]) -> Option<(Tz, JsProbe)> {
+    probes
+        .iter()
+        .find_map(|probe| probe.probe().map(|tz| (tz, *probe)))
+}
+
+#[cfg(all(feature = "std", target_family = "wasm"))]
+impl ProviderChain {
+    /// Returns the built-in providers on wasm: the JavaScript probes in the order
+    /// of [`js_precedence()`] (with the `js` feature), and then the `TZ` environment variable
+    /// of the host (e.g. under WASI).
+    #[allow(unused_mut)]
+    fn platform() -> Self {
+        let mut chain = Self::new();
+        #[cfg(feature = "js")]
+        for probe in js_precedence().probes() {
+            chain.push(probe);
+        }
+        #[cfg(feature = "probe-env")]
+        chain.push(EnvProvider::default());
+        chain
+    }
+}
+
+#[cfg(all(target_family = "wasm", feature = "wasm-bindgen"))]
+#[wasm_bindgen::prelude::wasm_bindgen(js_name = systemTz)]
+#[must_use]
+/// Returns the IANA name of the system timezone to JavaScript (`undefined` if not detected).
+pub fn system_tz_js() -> Option<String> {
+    try_system_tz().ok().map(|tz| tz.name().to_owned())
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// JavaScript global scope the crate runs in.
+///
+/// Detection only relies on `Intl`, which lives on the global scope (`self` in workers,
+/// `window` in documents, `globalThis` everywhere else), so it works in all of them.
+pub enum JsContext {
+    /// Browser document (`window`).
+    Window,
+    /// Dedicated Web Worker.
+    DedicatedWorker,
+    /// Shared Web Worker.
+    SharedWorker,
+    /// Service Worker.
+    ServiceWorker,
+    /// Any other host (e.g. Node.js, Deno).
+    Other,
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+impl JsContext {
+    #[must_use]
+    /// Returns the current global scope, from the name of its constructor.
+    pub fn current() -> Self {
+        use js_sys::Reflect;
+
+        let name = Reflect::get(&js_sys::global(), &"constructor".into())
+            .and_then(|constructor| Reflect::get(&constructor, &"name".into()))
+            .ok()
+            .and_then(|name| name.as_string());
+
+        match name.as_deref() {
+            Some("Window") => Self::Window,
+            Some("DedicatedWorkerGlobalScope") => Self::DedicatedWorker,
+            Some("SharedWorkerGlobalScope") => Self::SharedWorker,
+            Some("ServiceWorkerGlobalScope") => Self::ServiceWorker,
+            _ => Self::Other,
+        }
+    }
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+/// Period (in milliseconds) at which [`watch()`] revalidates the timezone.
+const WATCH_INTERVAL: i32 = 60_000;
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+/// Calls the JavaScript method `name` of `target` with `args`.
+fn js_call(
+    target: &wasm_bindgen::JsValue,
+    name: &str,
+    args: &[&wasm_bindgen::JsValue],
+) -> Option<wasm_bindgen::JsValue> {
+    use {js_sys::Function, js_sys::Reflect, wasm_bindgen::JsCast};
+
+    Reflect::get(target, &name.into())
+        .ok()?
+        .dyn_into::<Function>()
+        .ok()?
+        .apply(target, &args.iter().copied().collect())
+        .ok()
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+/// Handle of the JavaScript listeners watching the host timezone (see [`watch()`]),
+/// which are removed when dropped.
+pub struct Watcher {
+    listener: wasm_bindgen::closure::Closure<dyn FnMut()>,
+    document: Option<wasm_bindgen::JsValue>,
+    interval: wasm_bindgen::JsValue,
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        if let Some(document) = &self.document {
+            let _ = js_call(
+                document,
+                "removeEventListener",
+                &[&"visibilitychange".into(), self.listener.as_ref()],
+            );
+        }
+        let _ = js_call(&js_sys::global(), "clearInterval", &[&self.interval]);
+    }
+}
+
+#[cfg(all(target_family = "wasm", feature = "js"))]
+#[must_use]
+/// Watches the timezone resolved by the JavaScript `Intl` API,
+/// calling `callback` with the new timezone whenever it changes.
+///
+/// Browsers update it when the operating system timezone changes (e.g. when traveling
+/// with a laptop). The timezone is revalidated every minute and, in a [`JsContext::Window`],
+/// whenever the page becomes visible again (`visibilitychange` event).
+///
+/// Returns `None` if the host provides no timer (`setInterval`).
+pub fn watch<F>(mut callback: F) -> Option<Watcher>
+where
+    F: FnMut(Option<Tz>) + 'static,
+{
+    use wasm_bindgen::closure::Closure;
+
+    let mut current = js_tz();
+    let listener = Closure::<dyn FnMut()>::new(move || {
+        let tz = js_tz();
+        if tz != current {
+            current = tz;
+            callback(tz);
+        }
+    });
+
+    let global = js_sys::global();
+    let interval = js_call(
+        &global,
+        "setInterval",
+        &[listener.as_ref(), &WATCH_INTERVAL.into()],
+    )?;
+    // Workers have no `document`
+    let document = (JsContext::current() == JsContext::Window)
+        .then(|| js_sys::Reflect::get(&global, &"document".into()).ok())
+        .flatten()
+        .filter(|document| {
+            js_call(
+                document,
+                "addEventListener",
+                &[&"visibilitychange".into(), listener.as_ref()],
+            )
+            .is_some()
+        });
+
+    Some(Watcher {
+        listener,
+        document,
+        interval,
+    })
 }
@@ -1,5 +1,75 @@
+/// Provider reading `raw` from `source` after `delay`, detecting `tz` if set
+/// or else parsing the name at the end of `raw` in the mode of the chain.
+struct Stub {
+    source: super::DetectionSource,
+    raw: Option<&'static str>,
+    tz: Option<chrono_tz::Tz>,
+    delay: std::time::Duration,
+}
+
+impl Stub {
+    /// Returns a provider reading nothing from `source`.
+    const fn new(source: super::DetectionSource) -> Self {
+        Self {
+            source,
+            raw: None,
+            tz: None,
+            delay: std::time::Duration::ZERO,
+        }
+    }
+
+    /// Returns a provider reading nothing from a custom source named `name`.
+    const fn custom(name: &'static str) -> Self {
+        Self::new(super::DetectionSource::Custom(name))
+    }
+
+    /// Sets the raw value read.
+    const fn raw(self, raw: &'static str) -> Self {
+        Self {
+            raw: Some(raw),
+            ..self
+        }
+    }
+
+    /// Sets the timezone detected.
+    const fn tz(self, tz: chrono_tz::Tz) -> Self {
+        Self {
+            tz: Some(tz),
+            ..self
+        }
+    }
+
+    /// Sets the time spent reading.
+    const fn delay(self, delay: std::time::Duration) -> Self {
+        Self { delay, ..self }
+    }
+}
+
+impl super::TzProvider for Stub {
+    fn source(&self) -> super::DetectionSource {
+        self.source
+    }
+
+    fn read(&self) -> super::Reading {
+        self.read_in(super::ParseMode::default())
+    }
+
+    fn read_in(&self, mode: super::ParseMode) -> super::Reading {
+        std::thread::sleep(self.delay);
+        let tz = match (self.tz, self.raw) {
+            (Some(tz), _) => Ok(tz),
+            (None, Some(raw)) => mode.read(raw.rsplit("zoneinfo/").next().unwrap_or_default()),
+            (None, None) => Err(super::Error::UnknownTimezone),
+        };
+        super::Reading {
+            raw: self.raw.map(str::to_owned),
+            tz,
+        }
+    }
+}
+
 #[test]
-#[cfg(target_family = "windows")]
+#[cfg(any(target_family = "windows", feature = "windows-zones"))]
 fn is_handles_windows_tz() {
     use super::WindowsTz;
 
@@ -7,25 +77,1197 @@ fn is_handles_windows_tz() {
         "Timezone doesn't exist in latest version of `WindowsZones` CLDR dataset";
 
     assert_eq!(
-        chrono_tz::Tz::from(
+        chrono_tz::Tz::try_from(
             WindowsTz::get("US Mountain Standard Time", Some("CA")).expect(missing_windows_tz)
         ),
-        chrono_tz::America::Creston
+        Ok(chrono_tz::America::Creston)
     );
 
     assert_eq!(
-        chrono_tz::Tz::from(
+        chrono_tz::Tz::try_from(
             WindowsTz::get("US Mountain Standard Time", None).expect(missing_windows_tz)
         ),
-        chrono_tz::America::Phoenix
+        Ok(chrono_tz::America::Phoenix)
     );
 
     assert_eq!(
-        WindowsTz::try_from(chrono_tz::Europe::Vienna).ok().as_ref(),
+        WindowsTz::try_from(&chrono_tz::Europe::Vienna)
+            .ok()
+            .as_ref(),
         WindowsTz::get("W. Europe Standard Time", Some("AT"))
     );
 
     let case = chrono_tz::Europe::Paris;
-    let windows = WindowsTz::try_from(case).expect(missing_windows_tz);
-    assert_eq!(case, chrono_tz::Tz::from(&windows));
+    let windows = WindowsTz::try_from(&case).expect(missing_windows_tz);
+    assert_eq!(Ok(case), chrono_tz::Tz::try_from(&windows));
+}
+
+#[test]
+#[cfg(any(target_family = "windows", feature = "windows-zones"))]
+fn it_queries_windows_zones() {
+    use super::{WindowsTz, WindowsZone, WindowsZonesVersion};
+
+    assert!(WindowsTz::all().count() > 100);
+
+    let romance = WindowsTz::from_iana(&chrono_tz::Europe::Paris);
+    assert_eq!(romance.len(), 2);
+    assert!(romance.iter().all(
+        |x| Some(*x) == WindowsTz::get("Romance Standard Time", None)
+            || Some(*x) == WindowsTz::get("Romance Standard Time", Some("FR"))
+    ));
+
+    assert!(WindowsTz::from_iana(&chrono_tz::Tz::Antarctica__Troll).is_empty());
+
+    let creston = WindowsTz::get("US Mountain Standard Time", Some("CA"))
+        .expect("Timezone doesn't exist in latest version of `WindowsZones` CLDR dataset");
+    assert_eq!(creston.zone(), WindowsZone::UsMountainStandardTime);
+    assert_eq!(creston.zone().to_string(), "US Mountain Standard Time");
+    assert_eq!("UTC-02".parse::<WindowsZone>(), Ok(WindowsZone::UtcMinus02));
+    assert!("Unknown Standard Time".parse::<WindowsZone>().is_err());
+    assert_eq!(creston.territory(), Some("CA"));
+    assert_eq!(creston.iana().first(), Some(&"America/Creston"));
+    assert_eq!(creston.timezones().len(), creston.iana().len());
+
+    assert_eq!(
+        WindowsTz::territories("US Mountain Standard Time").collect::<Vec<_>>(),
+        ["001", "CA", "MX", "US", "ZZ"]
+    );
+    assert_eq!(WindowsTz::territories("Unknown Standard Time").count(), 0);
+
+    let version = WindowsZonesVersion::bundled();
+    assert_eq!(version.release(), "release-44");
+    assert!(version.to_string().starts_with("CLDR release-44"));
+    assert_eq!(
+        version.sha256(),
+        "a7bb485ff97e225709f08f4fcc657b84c2589b3560f0e1af2289a40112ca6cbd"
+    );
+    assert_eq!(WindowsTz::hash(), Some(0xa7bb_485f_f97e_2257));
+}
+
+#[test]
+#[cfg(feature = "runtime-cldr")]
+fn it_diffs_windows_zones() {
+    use super::{WindowsTz, WindowsZonesChange};
+
+    let xml = include_str!("../data/windowsZones.xml");
+    assert_eq!(WindowsTz::diff_xml(xml), Ok(vec![]));
+
+    let xml = xml
+        .replace(
+            r#"territory="CA" type="America/Creston America/Dawson_Creek America/Fort_Nelson""#,
+            r#"territory="CA" type="America/Creston""#,
+        )
+        .replace(
+            r#"<mapZone other="Romance Standard Time" territory="FR" type="Europe/Paris"/>"#,
+            "",
+        );
+    let changes = WindowsTz::diff_xml(&xml).expect("Invalid XML");
+    assert_eq!(changes.len(), 2);
+    assert!(changes.contains(&WindowsZonesChange::Removed(
+        WindowsTz::get("Romance Standard Time", Some("FR")).expect("Missing mapping")
+    )));
+    assert!(changes.contains(&WindowsZonesChange::Changed {
+        from: WindowsTz::get("US Mountain Standard Time", Some("CA")).expect("Missing mapping"),
+        iana: vec!["America/Creston".into()],
+    }));
+
+    // IANA names unknown to `chrono-tz` are reported as well
+    let xml = include_str!("../data/windowsZones.xml")
+        .replace(
+            r#"territory="FR" type="Europe/Paris""#,
+            r#"territory="FR" type="Europe/Paris Mars/Tharsis""#,
+        )
+        .replace(
+            "</mapTimezones>",
+            r#"<mapZone other="Romance Standard Time" territory="MA" type="Mars/Olympus"/></mapTimezones>"#,
+        );
+    let changes = WindowsTz::diff_xml(&xml).expect("Invalid XML");
+    assert_eq!(changes.len(), 2);
+    assert!(changes.contains(&WindowsZonesChange::Changed {
+        from: WindowsTz::get("Romance Standard Time", Some("FR")).expect("Missing mapping"),
+        iana: vec!["Europe/Paris".into(), "Mars/Tharsis".into()],
+    }));
+    assert!(changes.contains(&WindowsZonesChange::Added {
+        zone: "Romance Standard Time".into(),
+        territory: Some("MA".into()),
+        iana: vec!["Mars/Olympus".into()],
+    }));
+}
+
+#[test]
+#[cfg(feature = "runtime-cldr")]
+fn it_loads_windows_zones() {
+    use super::{WindowsTz, WindowsZone};
+
+    // Same mappings as the bundled ones, so that concurrent tests aren't affected
+    let xml = include_str!("../data/windowsZones.xml").replace(
+        r#"territory="FR" type="Europe/Paris""#,
+        r#"territory="FR" type="Europe/Paris Mars/Tharsis""#,
+    );
+
+    let count = WindowsTz::load_from_xml(&xml).expect("Invalid XML");
+    let paris = WindowsTz::get("Romance Standard Time", Some("FR")).expect("Missing mapping");
+    assert_eq!(paris.iana(), ["Europe/Paris"]);
+
+    // Loaded mappings replace the bundled ones instead of duplicating them
+    let mut seen = std::collections::BTreeSet::new();
+    assert!(WindowsTz::all().all(|x| seen.insert((x.zone(), x.territory()))));
+
+    // Loading again reuses the mappings instead of allocating them again
+    assert_eq!(WindowsTz::load_from_xml(&xml), Ok(count));
+    assert!(core::ptr::eq(
+        paris,
+        WindowsTz::get("Romance Standard Time", Some("FR")).expect("Missing mapping")
+    ));
+
+    // Zones missing from the bundled dataset are kept
+    let xml = xml.replace(
+        "</mapTimezones>",
+        r#"<mapZone other="Mars Standard Time" territory="001" type="Pacific/Chatham"/></mapTimezones>"#,
+    );
+    assert_eq!(WindowsTz::load_from_xml(&xml), Ok(count + 1));
+    let mars = WindowsTz::get("Mars Standard Time", None).expect("Missing zone");
+    assert_eq!(mars.zone(), WindowsZone::Other("Mars Standard Time"));
+    assert_eq!(mars.zone().to_string(), "Mars Standard Time");
+}
+
+#[test]
+#[cfg(target_family = "windows")]
+fn it_maps_custom_utc_zones() {
+    use super::custom_utc_zone;
+
+    assert_eq!(custom_utc_zone("UTC+05"), Some(chrono_tz::Etc::GMTMinus5));
+    assert_eq!(custom_utc_zone("UTC-02:00"), Some(chrono_tz::Etc::GMTPlus2));
+    assert_eq!(custom_utc_zone("UTC+14"), Some(chrono_tz::Etc::GMTMinus14));
+    assert_eq!(custom_utc_zone("UTC+05:30"), None);
+    assert_eq!(custom_utc_zone("UTC+15"), None);
+    assert_eq!(custom_utc_zone("Romance Standard Time"), None);
+}
+
+#[test]
+#[cfg(feature = "localized-names")]
+fn it_localizes_timezone_names() {
+    use super::LocalizedTz;
+
+    let berlin = chrono_tz::Europe::Berlin;
+    assert_eq!(berlin.metazone(), Some("Europe_Central"));
+
+    let names = berlin
+        .localized_name("de-AT")
+        .expect("Missing German names");
+    assert_eq!(names.generic, Some("Mitteleuropäische Zeit"));
+    assert_eq!(names.daylight, Some("Mitteleuropäische Sommerzeit"));
+
+    assert_eq!(
+        chrono_tz::Europe::London
+            .localized_name("en")
+            .and_then(|names| names.standard),
+        Some("Greenwich Mean Time")
+    );
+    assert_eq!(berlin.localized_name("xx"), None);
+    assert_eq!(chrono_tz::Antarctica::Troll.metazone(), None);
+
+    assert_eq!(
+        super::display_name(berlin, "fr"),
+        Some("heure d’Europe centrale")
+    );
+    assert_eq!(
+        super::display_name(chrono_tz::Antarctica::Troll, "fr"),
+        None
+    );
+}
+
+#[test]
+#[cfg(target_family = "unix")]
+fn it_resolves_zoneinfo_links() {
+    use super::{symlink_chain, zoneinfo_link, ParseMode, MAX_SYMLINKS};
+    use ::std::{fs, os::unix::fs::symlink};
+
+    let root = std::env::temp_dir().join(format!("system_tz-links-{}", std::process::id()));
+    let zoneinfo = root.join("zoneinfo/posix/Europe");
+    fs::create_dir_all(&zoneinfo).expect("Failed to create test directory");
+    fs::write(zoneinfo.join("Paris"), b"TZif").expect("Failed to create test file");
+
+    // Relative target, multi-level chain
+    symlink("zoneinfo/posix/Europe/Paris", root.join("target")).expect("Failed to link");
+    symlink("target", root.join("localtime")).expect("Failed to link");
+    let chain = symlink_chain(&super::Host::Os, root.join("localtime"));
+    assert_eq!(chain.len(), 3);
+    assert_eq!(
+        zoneinfo_link(&chain, ParseMode::Lenient),
+        Some(chrono_tz::Europe::Paris)
+    );
+
+    // Loop
+    symlink("loop_b", root.join("loop_a")).expect("Failed to link");
+    symlink("loop_a", root.join("loop_b")).expect("Failed to link");
+    let chain = symlink_chain(&super::Host::Os, root.join("loop_a"));
+    assert_eq!(chain.len(), MAX_SYMLINKS + 1);
+    assert_eq!(zoneinfo_link(&chain, ParseMode::Lenient), None);
+
+    // Missing
+    assert!(symlink_chain(&super::Host::Os, root.join("missing")).is_empty());
+
+    // Database installed under another `zoneinfo` directory
+    assert_eq!(
+        super::zoneinfo_name("/opt/zoneinfo/share/zoneinfo/right/Asia/Tokyo".as_ref()),
+        Some("Asia/Tokyo")
+    );
+
+    fs::remove_dir_all(root).expect("Failed to remove test directory");
+}
+
+#[test]
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+fn it_reads_user_settings() {
+    use super::{environment_d_tz, system_tz_for_user, user_home, User};
+    use ::std::fs;
+
+    let dir = std::env::temp_dir().join(format!("system_tz-environment-{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("Failed to create test directory");
+    assert_eq!(environment_d_tz(&dir), None);
+
+    fs::write(
+        dir.join("10-tz.conf"),
+        "LANG=fr_FR.UTF-8\nTZ=Europe/Paris\n",
+    )
+    .expect("Failed to create test file");
+    fs::write(dir.join("20-tz.conf"), "# Travelling\nTZ=\"Asia/Tokyo\"\n")
+        .expect("Failed to create test file");
+    fs::write(dir.join("30-tz.conf.disabled"), "TZ=UTC\n").expect("Failed to create test file");
+    assert_eq!(environment_d_tz(&dir), Some(chrono_tz::Asia::Tokyo));
+
+    fs::remove_dir_all(dir).expect("Failed to remove test directory");
+
+    assert!(user_home(&User::Id(0)).is_some());
+    assert_eq!(user_home(&"root".into()), user_home(&User::Id(0)));
+    assert_eq!(system_tz_for_user("no-such-user-system-tz"), None);
+}
+
+#[test]
+#[cfg(all(target_family = "unix", feature = "probe-localtime"))]
+fn it_detects_under_root() {
+    use super::{system_tz_under, DetectionSource, Host, ProviderChain};
+    use ::std::{fs, os::unix::fs::symlink};
+
+    let root = std::env::temp_dir().join(format!("system_tz-root-{}", std::process::id()));
+    fs::create_dir_all(root.join("etc")).expect("Failed to create test directory");
+    fs::create_dir_all(root.join("usr/share/zoneinfo/Asia"))
+        .expect("Failed to create test directory");
+    fs::write(root.join("usr/share/zoneinfo/Asia/Tokyo"), b"TZif")
+        .expect("Failed to create test file");
+
+    // Absolute target, resolved under the root
+    symlink("/usr/share/zoneinfo/Asia/Tokyo", root.join("etc/localtime")).expect("Failed to link");
+    assert_eq!(system_tz_under(&root), Some(chrono_tz::Asia::Tokyo));
+    assert!(!ProviderChain::under(&root)
+        .sources()
+        .any(|source| source == DetectionSource::EnvTz || source.is_exec()));
+
+    // Files behind absolute links are read under the root, not from the running system
+    fs::create_dir_all(root.join("system_tz-fixture")).expect("Failed to create test directory");
+    fs::write(root.join("system_tz-fixture/timezone"), "Asia/Tokyo\n")
+        .expect("Failed to create test file");
+    symlink("/system_tz-fixture/timezone", root.join("etc/timezone")).expect("Failed to link");
+    let host = Host::Under(root.as_path().into());
+    let path = std::path::Path::new("/etc/timezone");
+    assert_eq!(
+        host.read_to_string(path).expect("Failed to read"),
+        "Asia/Tokyo\n"
+    );
+    assert_eq!(host.len(path).expect("Failed to stat"), 11);
+
+    fs::remove_dir_all(root).expect("Failed to remove test directory");
+}
+
+#[test]
+fn it_gets_system_tz_without_trait() {
+    use super::SystemTz;
+
+    assert_eq!(super::system_tz(), chrono_tz::Tz::system_tz());
+}
+
+#[test]
+#[cfg(all(target_family = "unix", feature = "probe-env"))]
+fn it_reports_probe_failures() {
+    use super::{DetectionSource, EnvProvider, Error, FileProvider, TzProvider};
+
+    assert_eq!(
+        EnvProvider::new("SYSTEM_TZ_TEST_UNSET_VARIABLE").read().tz,
+        Err(Error::MissingVariable)
+    );
+    assert_eq!(
+        FileProvider::new(DetectionSource::EtcTimezone, "/nonexistent/timezone")
+            .read()
+            .tz,
+        Err(Error::Io {
+            path: Some("/nonexistent/timezone".into()),
+            kind: std::io::ErrorKind::NotFound,
+            message: "No such file or directory (os error 2)".into(),
+        })
+    );
+    assert_eq!(
+        Error::io(
+            "/etc/timezone",
+            &std::io::ErrorKind::PermissionDenied.into()
+        )
+        .to_string(),
+        "Permission denied on `/etc/timezone`"
+    );
+    assert_eq!(
+        Error::from(std::io::Error::from(std::io::ErrorKind::NotFound)),
+        Error::Io {
+            path: None,
+            kind: std::io::ErrorKind::NotFound,
+            message: "entity not found".into(),
+        }
+    );
+    assert_eq!(
+        Error::io("/etc/timezone", &std::io::Error::other("disk on fire")).to_string(),
+        "I/O error on `/etc/timezone`: disk on fire"
+    );
+
+    match super::try_system_tz() {
+        Ok(tz) => assert_eq!(super::system_tz(), Some(tz)),
+        Err(Error::Probes(errors)) => assert!(errors
+            .iter()
+            .any(|err| err.probe == super::DetectionSource::EnvTz)),
+        Err(err) => panic!("unexpected error: {err}"),
+    }
+}
+
+#[test]
+#[cfg(all(
+    target_family = "unix",
+    not(feature = "hardened"),
+    feature = "probe-env"
+))]
+fn it_reports_detection() {
+    let report = super::system_tz_report();
+
+    assert_eq!(report.tz, super::system_tz());
+    assert_eq!(
+        report.probes[..3]
+            .iter()
+            .map(|probe| probe.probe)
+            .collect::<Vec<_>>(),
+        [
+            super::DetectionSource::Override,
+            super::DetectionSource::EnvSystemTz,
+            super::DetectionSource::EnvTz
+        ]
+    );
+    assert!(report.probes[0].ran());
+    if let Some(index) = report
+        .probes
+        .iter()
+        .position(|probe| matches!(probe.result, Some(Ok(_))))
+    {
+        assert!(report.probes[index + 1..].iter().all(|probe| !probe.ran()));
+    }
+    assert!(report
+        .probes
+        .iter()
+        .all(|probe| probe.ran() == probe.elapsed.is_some()));
+    assert!(report.elapsed >= Some(report.probes.iter().filter_map(|probe| probe.elapsed).sum()));
+
+    let report = super::ProviderChain::system().report_all();
+    assert_eq!(report.tz, super::system_tz());
+    assert!(report.probes.iter().all(super::ProbeReport::ran));
+}
+
+#[test]
+fn it_tells_heuristic_sources() {
+    use super::{Confidence, DetectionSource};
+
+    assert!(DetectionSource::DateCommand.is_heuristic());
+    assert!(!DetectionSource::LocaltimeSymlink.is_heuristic());
+    assert_eq!(
+        DetectionSource::DateCommand.confidence(),
+        Confidence::Heuristic
+    );
+    assert_eq!(
+        DetectionSource::LocaltimeSymlink.confidence(),
+        Confidence::Configured
+    );
+    assert_eq!(
+        DetectionSource::EnvTz.confidence(),
+        Confidence::Authoritative
+    );
+    assert_eq!(
+        DetectionSource::WindowsWine.confidence(),
+        Confidence::Authoritative
+    );
+    assert_eq!(
+        DetectionSource::WindowsStandardName.confidence(),
+        Confidence::Heuristic
+    );
+    assert!(Confidence::Heuristic < Confidence::Configured);
+    assert_eq!(
+        super::system_tz_with_confidence().map(|(tz, _)| tz),
+        super::system_tz()
+    );
+    assert_eq!(DetectionSource::EnvTz.to_string(), "TZ");
+    assert_eq!(DetectionSource::LocaltimeSymlink.id(), "etc_localtime");
+    assert_eq!(
+        super::system_tz_with_source().map(|(tz, _)| tz),
+        super::system_tz()
+    );
+}
+
+#[test]
+fn it_chains_custom_providers() {
+    use super::{DetectionSource, Error, ProbeError, ProviderChain};
+
+    let chain = ProviderChain::new()
+        .with(Stub::custom("fixed").tz(chrono_tz::Asia::Tokyo))
+        .with_first(Stub::custom("fixed"));
+    assert_eq!(
+        chain.detect_with_source(),
+        Some((chrono_tz::Asia::Tokyo, DetectionSource::Custom("fixed")))
+    );
+
+    assert_eq!(
+        ProviderChain::new().with(Stub::custom("fixed")).detect(),
+        Err(Error::Probes(vec![ProbeError {
+            probe: DetectionSource::Custom("fixed"),
+            cause: Error::UnknownTimezone,
+        }]))
+    );
+}
+
+#[test]
+fn it_ranks_candidates() {
+    use super::{Candidate, Confidence, DetectionSource, ProviderChain};
+
+    let candidates = ProviderChain::new()
+        .with(Stub::new(DetectionSource::DateCommand).tz(chrono_tz::Etc::GMTMinus1))
+        .with(Stub::new(DetectionSource::EnvTz))
+        .with(Stub::new(DetectionSource::EtcTimezone).tz(chrono_tz::Europe::Paris))
+        .with(Stub::new(DetectionSource::LocaltimeSymlink).tz(chrono_tz::Europe::Berlin))
+        .with(Stub::new(DetectionSource::LocaltimeCopy).tz(chrono_tz::Europe::Paris))
+        .candidates();
+    assert_eq!(
+        candidates,
+        vec![
+            Candidate {
+                tz: chrono_tz::Europe::Paris,
+                sources: vec![DetectionSource::EtcTimezone, DetectionSource::LocaltimeCopy],
+            },
+            Candidate {
+                tz: chrono_tz::Europe::Berlin,
+                sources: vec![DetectionSource::LocaltimeSymlink],
+            },
+            Candidate {
+                tz: chrono_tz::Etc::GMTMinus1,
+                sources: vec![DetectionSource::DateCommand],
+            },
+        ]
+    );
+    assert_eq!(
+        candidates
+            .iter()
+            .map(Candidate::confidence)
+            .collect::<Vec<_>>(),
+        [
+            Confidence::Configured,
+            Confidence::Configured,
+            Confidence::Heuristic
+        ]
+    );
+}
+
+#[test]
+fn it_times_out_slow_providers() {
+    use super::{DetectionSource, Error, ProviderChain};
+    use std::time::Duration;
+
+    // Margins keep the fast provider well within the timeout on loaded machines,
+    // and the hung one well beyond it
+    let timeout = Duration::from_secs(1);
+    let paris = |name| Stub::custom(name).tz(chrono_tz::Europe::Paris);
+
+    let chain = ProviderChain::new()
+        .with(paris("hung").delay(Duration::from_secs(3600)))
+        .with(paris("fast"))
+        .probe_timeout(timeout);
+    let report = chain.report();
+    assert_eq!(report.tz, Some(chrono_tz::Europe::Paris));
+    assert_eq!(report.probes[0].result, Some(Err(Error::Timeout)));
+    assert!(report.probes[0].elapsed >= Some(timeout));
+    assert!(report.to_string().contains("hung: Timed out in "));
+    assert_eq!(
+        chain.detect_with_source(),
+        Some((chrono_tz::Europe::Paris, DetectionSource::Custom("fast")))
+    );
+
+    let report = ProviderChain::new()
+        .with(paris("hung").delay(Duration::from_secs(3600)))
+        .with(paris("fast"))
+        .timeout(timeout)
+        .report();
+    assert_eq!(report.tz, None);
+    assert!(report
+        .probes
+        .iter()
+        .all(|probe| probe.result == Some(Err(Error::Timeout))));
+}
+
+#[test]
+#[cfg(all(
+    target_family = "unix",
+    not(feature = "hardened"),
+    feature = "probe-env",
+    feature = "probe-etc-timezone",
+    feature = "probe-localtime"
+))]
+fn it_selects_providers_with_options() {
+    use super::{DetectionSource, SystemTzOptions};
+
+    let sources = |options: SystemTzOptions| options.chain().sources().collect::<Vec<_>>();
+
+    let all = sources(SystemTzOptions::new());
+    assert_eq!(all.get(2), Some(&DetectionSource::EnvTz));
+
+    let without_env = sources(SystemTzOptions::new().env(false));
+    assert!(!without_env.contains(&DetectionSource::EnvTz));
+    assert_eq!(without_env.len(), all.len() - 1);
+
+    assert!(sources(SystemTzOptions::new().read_only(true))
+        .iter()
+        .all(|source| source.is_read_only()));
+    assert!(!sources(SystemTzOptions::new().exec(false))
+        .iter()
+        .any(|source| source.is_exec()));
+
+    let reordered = sources(
+        SystemTzOptions::new().order([DetectionSource::LocaltimeSymlink, DetectionSource::EnvTz]),
+    );
+    assert_eq!(
+        reordered[..5],
+        [
+            DetectionSource::Override,
+            DetectionSource::EnvSystemTz,
+            DetectionSource::LocaltimeSymlink,
+            DetectionSource::EnvTz,
+            DetectionSource::EtcTimezone
+        ]
+    );
+}
+
+#[test]
+#[cfg(all(target_family = "unix", feature = "probe-env"))]
+fn it_ignores_the_environment_when_hardened() {
+    use super::{DetectionSource, Host, SystemTzOptions};
+
+    let sources = SystemTzOptions::new()
+        .hardened(true)
+        .chain()
+        .sources()
+        .collect::<Vec<_>>();
+    assert_eq!(sources.first(), Some(&DetectionSource::Override));
+    assert!(!sources.iter().any(|source| source.reads_env()));
+    assert!(Host::Hardened.var("PATH").is_err());
+    assert!(DetectionSource::SystemdUserEnvironment.reads_env());
+    assert!(!DetectionSource::LocaltimeSymlink.reads_env());
+}
+
+#[test]
+#[cfg(all(feature = "hardened", not(target_family = "windows")))]
+fn it_ignores_tz_for_the_offset_when_hardened() {
+    use super::{system_offset, system_tz_or_offset, Detected};
+
+    // Runs in a child process, as setting `TZ` here would race with the other tests
+    if std::env::var_os("SYSTEM_TZ_TEST_CHILD").is_none() {
+        let status = std::process::Command::new(std::env::current_exe().expect("No test binary"))
+            .args([
+                "--exact",
+                "test::it_ignores_tz_for_the_offset_when_hardened",
+            ])
+            .env("SYSTEM_TZ_TEST_CHILD", "1")
+            .env("TZ", "XXX-14")
+            .status()
+            .expect("Failed to run the test binary");
+        assert!(status.success());
+        return;
+    }
+
+    // `chrono::Local` would report the offset of this POSIX rule
+    let offset = chrono::FixedOffset::east_opt(14 * 3600).expect("Invalid offset");
+    assert_eq!(system_offset(), None);
+    assert_ne!(system_tz_or_offset(), Some(Detected::Offset(offset)));
+}
+
+#[test]
+#[cfg(target_family = "unix")]
+fn it_stops_watching_when_dropped() {
+    let (watcher, receiver) = super::watch_channel().expect("Failed to spawn watcher");
+    drop(watcher);
+    assert!(receiver.recv().is_err());
+}
+
+#[test]
+fn it_falls_back_to_offset() {
+    use super::{system_tz_or_offset, Detected};
+
+    assert_eq!(
+        Detected::Iana(chrono_tz::Europe::Paris).to_string(),
+        "Europe/Paris"
+    );
+    assert_eq!(
+        Detected::Offset(chrono::FixedOffset::east_opt(19800).unwrap()).to_string(),
+        "+05:30"
+    );
+    assert!(system_tz_or_offset().is_some());
+}
+
+#[test]
+fn it_parses_strictly() {
+    use super::{DetectionSource, Error, ParseMode, ProviderChain};
+
+    assert_eq!(
+        ParseMode::Strict.parse("Asia/Kolkata"),
+        Some(chrono_tz::Asia::Kolkata)
+    );
+    assert_eq!(ParseMode::Strict.parse("UTC"), Some(chrono_tz::UTC));
+    assert_eq!(ParseMode::Strict.parse("Asia/Calcutta"), None);
+    assert_eq!(ParseMode::Strict.parse("europe/amsterdam"), None);
+    assert_eq!(
+        ParseMode::Lenient.parse("europe/amsterdam"),
+        Some(chrono_tz::Europe::Amsterdam)
+    );
+    assert_eq!(
+        ParseMode::Lenient.parse("US/Eastern"),
+        Some(chrono_tz::US::Eastern)
+    );
+
+    // Values read by the providers may use the POSIX `TZ` form or be quoted
+    assert_eq!(
+        ParseMode::Strict.read(":Europe/Paris"),
+        Ok(chrono_tz::Europe::Paris)
+    );
+    assert_eq!(
+        ParseMode::Strict.read("\"Europe/Paris\"\n"),
+        Ok(chrono_tz::Europe::Paris)
+    );
+    assert_eq!(
+        ParseMode::Strict.read("europe/paris"),
+        Err(Error::NonCanonical)
+    );
+    assert_eq!(
+        ParseMode::Strict.read("Mars/Tharsis"),
+        Err(Error::Parse {
+            raw: "Mars/Tharsis".into()
+        })
+    );
+
+    let chain = ProviderChain::new()
+        .with(Stub::new(DetectionSource::EnvTz).raw("us/pacific"))
+        .with(Stub::new(DetectionSource::LocaltimeSymlink).raw("/usr/share/zoneinfo/US/Pacific"))
+        .with(Stub::new(DetectionSource::JsDateOffset).raw("Etc/GMT+8"))
+        .with(Stub::new(DetectionSource::EtcTimezone).raw("America/Los_Angeles"));
+    assert_eq!(chain.detect(), Ok(chrono_tz::US::Pacific));
+
+    let chain = chain.mode(ParseMode::Strict);
+    assert_eq!(chain.sources().count(), 3);
+    let report = chain.report();
+    assert_eq!(report.tz, Some(chrono_tz::America::Los_Angeles));
+    assert_eq!(report.probes[0].result, Some(Err(Error::NonCanonical)));
+    assert_eq!(report.probes[1].result, Some(Err(Error::NonCanonical)));
+}
+
+#[test]
+fn it_normalizes_deprecated_names() {
+    use super::{normalize, ProviderChain};
+
+    assert_eq!(
+        normalize(chrono_tz::US::Eastern),
+        chrono_tz::America::New_York
+    );
+    assert_eq!(
+        normalize(chrono_tz::Australia::ACT),
+        chrono_tz::Australia::Sydney
+    );
+    assert_eq!(normalize(chrono_tz::UTC), chrono_tz::UTC);
+
+    let chain = ProviderChain::new().with(Stub::custom("fixed").tz(chrono_tz::Asia::Calcutta));
+    assert_eq!(chain.detect(), Ok(chrono_tz::Asia::Calcutta));
+    assert_eq!(chain.normalize(true).detect(), Ok(chrono_tz::Asia::Kolkata));
+}
+
+#[test]
+fn it_searches_timezones() {
+    use super::search_tz;
+
+    assert_eq!(search_tz("Europe/Paris"), [chrono_tz::Europe::Paris]);
+    assert_eq!(search_tz(" PARI ").first(), Some(&chrono_tz::Europe::Paris));
+    assert_eq!(
+        search_tz("buenos aires").first(),
+        Some(&chrono_tz::America::Argentina::Buenos_Aires)
+    );
+    assert_eq!(search_tz("kolkta"), [chrono_tz::Asia::Kolkata]);
+    assert!(!search_tz("pari").contains(&chrono_tz::PRC));
+    assert!(!search_tz("york").contains(&chrono_tz::ROK));
+    assert!(search_tz("eastern").contains(&chrono_tz::America::New_York));
+    assert!(!search_tz("tokyo").contains(&chrono_tz::Asia::Calcutta));
+    assert!(search_tz("zzzz").is_empty());
+    assert!(search_tz(" ").is_empty());
+}
+
+#[test]
+#[cfg(all(
+    target_family = "unix",
+    feature = "test-util",
+    feature = "probe-env",
+    feature = "probe-etc-timezone",
+    feature = "probe-localtime",
+    feature = "probe-config-files"
+))]
+fn it_detects_on_mocked_systems() {
+    use super::{DetectionSource, MockSystem, ParseMode, ProviderChain};
+
+    let detect = |system: &MockSystem| ProviderChain::mocked(system.clone()).detect_with_source();
+
+    let system = MockSystem::new().file("/usr/share/zoneinfo/Europe/Paris", "TZif-paris");
+    assert_eq!(detect(&system), None);
+
+    let system = system.file("/etc/localtime", "TZif-paris");
+    assert_eq!(
+        detect(&system),
+        Some((chrono_tz::Europe::Paris, DetectionSource::LocaltimeCopy))
+    );
+    let report = ProviderChain::mocked(system.clone()).report();
+    let copy = report
+        .probes
+        .iter()
+        .find(|probe| probe.probe == DetectionSource::LocaltimeCopy);
+    assert_eq!(
+        copy.and_then(|probe| probe.raw.as_deref()),
+        Some("/usr/share/zoneinfo/Europe/Paris")
+    );
+
+    // Linked zones have identical files, of which deprecated aliases are ignored
+    let india = MockSystem::new()
+        .file("/usr/share/zoneinfo/Asia/Calcutta", "TZif-india")
+        .file("/usr/share/zoneinfo/Asia/Kolkata", "TZif-india")
+        .file("/etc/localtime", "TZif-india");
+    assert_eq!(
+        detect(&india),
+        Some((chrono_tz::Asia::Kolkata, DetectionSource::LocaltimeCopy))
+    );
+
+    let system = system.file("/etc/sysconfig/clock", "# comment\nZONE=\"Asia/Tokyo\"\n");
+    assert_eq!(
+        detect(&system),
+        Some((chrono_tz::Asia::Tokyo, DetectionSource::SysconfigClock))
+    );
+
+    let system = system
+        .file("/usr/share/zoneinfo/posix/Europe/Berlin", "TZif-berlin")
+        .symlink("/etc/localtime", "/usr/share/zoneinfo/posix/Europe/Berlin");
+    assert_eq!(
+        detect(&system),
+        Some((chrono_tz::Europe::Berlin, DetectionSource::LocaltimeSymlink))
+    );
+
+    let system = system.file("/etc/timezone", "America/Denver\n");
+    assert_eq!(
+        detect(&system),
+        Some((chrono_tz::America::Denver, DetectionSource::EtcTimezone))
+    );
+
+    let system = system.var("TZ", ":Asia/Kolkata");
+    assert_eq!(
+        detect(&system),
+        Some((chrono_tz::Asia::Kolkata, DetectionSource::EnvTz))
+    );
+
+    let system = system.var("SYSTEM_TZ", "UTC");
+    assert_eq!(
+        detect(&system),
+        Some((chrono_tz::UTC, DetectionSource::EnvSystemTz))
+    );
+
+    // Names spelled with another case are only accepted in lenient mode
+    let system = MockSystem::new().file("/etc/sysconfig/clock", "ZONE=\"europe/paris\"\n");
+    assert_eq!(
+        detect(&system),
+        Some((chrono_tz::Europe::Paris, DetectionSource::SysconfigClock))
+    );
+    let chain = ProviderChain::mocked(system).mode(ParseMode::Strict);
+    assert_eq!(chain.detect_with_source(), None);
+
+    let system = MockSystem::new().var("TZ", "Mars/Olympus_Mons");
+    let probe = ProviderChain::mocked(system)
+        .report()
+        .probes
+        .into_iter()
+        .find(|probe| probe.probe == DetectionSource::EnvTz);
+    assert_eq!(
+        probe.and_then(|probe| probe.result),
+        Some(Err(super::Error::Parse {
+            raw: "Mars/Olympus_Mons".into()
+        }))
+    );
+}
+
+#[test]
+#[cfg(all(
+    target_family = "unix",
+    feature = "test-util",
+    feature = "probe-env",
+    feature = "probe-etc-timezone",
+    feature = "probe-localtime",
+    feature = "probe-config-files"
+))]
+fn it_records_unknown_names() {
+    use super::{DetectionSource, Error, MockSystem, ProviderChain};
+
+    let system = MockSystem::new()
+        .file("/usr/share/zoneinfo/Mars/Tharsis", "TZif-mars")
+        .symlink("/etc/localtime", "/usr/share/zoneinfo/Mars/Tharsis")
+        .file("/etc/sysconfig/clock", "ZONE=\"Europe/Paris\"\n");
+
+    let report = ProviderChain::mocked(system.clone().var("TZ", "Mars/Olympus_Mons")).report();
+    assert_eq!(report.tz, Some(chrono_tz::Europe::Paris));
+    assert_eq!(report.raw_name(), Some("Mars/Olympus_Mons"));
+    assert_eq!(
+        report.unknown_names().collect::<Vec<_>>(),
+        [
+            (DetectionSource::EnvTz, "Mars/Olympus_Mons"),
+            (DetectionSource::LocaltimeSymlink, "Mars/Tharsis")
+        ]
+    );
+
+    // Paths and POSIX strings aren't unknown names
+    for tz in [":/etc/localtime", "CET-1CEST,M3.5.0,M10.5.0/3"] {
+        let report = ProviderChain::mocked(system.clone().var("TZ", tz))
+            .stop_on_unknown(true)
+            .report();
+        let env = report
+            .probes
+            .iter()
+            .find(|x| x.probe == DetectionSource::EnvTz);
+        assert_eq!(
+            env.and_then(|x| x.result.clone()),
+            Some(Err(Error::Unsupported { raw: tz.into() }))
+        );
+        assert_eq!(report.raw_name(), Some("Mars/Tharsis"));
+    }
+
+    let report = ProviderChain::mocked(system).stop_on_unknown(true).report();
+    assert_eq!(report.tz, None);
+    assert_eq!(report.raw_name(), Some("Mars/Tharsis"));
+    assert!(report
+        .probes
+        .iter()
+        .all(|probe| probe.ran() == (probe.probe <= DetectionSource::LocaltimeSymlink)));
+}
+
+#[test]
+fn it_resolves_abbreviations() {
+    use super::FromAbbreviation;
+    use chrono::FixedOffset;
+    use chrono_tz::Tz;
+
+    let cst = Tz::from_abbreviation("CST", None);
+    assert!(cst.contains(&chrono_tz::America::Chicago));
+    assert!(cst.contains(&chrono_tz::Asia::Shanghai));
+    assert!(cst.contains(&chrono_tz::America::Havana));
+
+    let cst = Tz::from_abbreviation("CST", FixedOffset::east_opt(-21600));
+    assert_eq!(cst.first(), Some(&chrono_tz::America::Chicago));
+    assert!(!cst.contains(&chrono_tz::Asia::Shanghai));
+    assert_eq!(
+        Tz::from_abbreviation(" CST ", FixedOffset::east_opt(28800)).first(),
+        Some(&chrono_tz::Asia::Shanghai)
+    );
+
+    assert!(Tz::from_abbreviation("cst", None).is_empty());
+    assert!(Tz::from_abbreviation("CET", FixedOffset::east_opt(0)).is_empty());
+    assert!(Tz::from_abbreviation("XYZ", None).is_empty());
+}
+
+#[test]
+fn it_guesses_timezones() {
+    use super::guess_tz;
+    use chrono::FixedOffset;
+
+    // Neither India nor Japan observe daylight saving time
+    let ist = FixedOffset::east_opt(19800).unwrap();
+    let jst = FixedOffset::east_opt(32400).unwrap();
+
+    assert_eq!(guess_tz(ist, Some("IN")), [chrono_tz::Asia::Kolkata]);
+    assert_eq!(guess_tz(ist, Some(" in ")), [chrono_tz::Asia::Kolkata]);
+    assert_eq!(
+        guess_tz(ist, None),
+        [chrono_tz::Asia::Colombo, chrono_tz::Asia::Kolkata]
+    );
+    assert!(guess_tz(ist, Some("JP")).is_empty());
+    assert!(guess_tz(ist, Some("XX")).is_empty());
+    assert_eq!(
+        super::guess_single_tz(ist, "IN"),
+        Some(chrono_tz::Asia::Kolkata)
+    );
+    assert_eq!(super::guess_single_tz(ist, "JP"), None);
+
+    let guesses = guess_tz(jst, None);
+    assert!(guesses.contains(&chrono_tz::Asia::Tokyo));
+    assert!(guesses.contains(&chrono_tz::Asia::Seoul));
+    assert!(!guesses.contains(&chrono_tz::Asia::Kolkata));
+
+    let zones = super::territory_tz(" us ");
+    assert!(zones.contains(&chrono_tz::America::New_York));
+    assert!(zones.windows(2).all(|pair| pair[0].name() < pair[1].name()));
+    assert!(super::territory_tz("XX").is_empty());
+}
+
+#[test]
+fn it_names_the_system_timezone() {
+    assert_eq!(
+        super::system_tz_name(),
+        super::system_tz().map(|tz| tz.name().to_owned())
+    );
+}
+
+#[test]
+#[cfg(feature = "time")]
+fn it_converts_to_time_types() {
+    use time_tz::TimeZone;
+
+    if let Some(name) = super::system_tz_name() {
+        assert_eq!(
+            super::system_tz_time().map(TimeZone::name),
+            Some(name.as_str())
+        );
+    }
+    let offset = super::system_utc_offset();
+    assert!(offset.is_none_or(|offset| offset.whole_hours().abs() <= 14));
+}
+
+#[test]
+#[cfg(feature = "jiff")]
+fn it_converts_to_jiff_types() {
+    if let Some(tz) = super::system_tz() {
+        let jiff = super::system_tz_jiff().unwrap();
+        assert_eq!(jiff.iana_name(), Some(tz.name()));
+    }
+}
+
+#[test]
+#[cfg(feature = "icu")]
+fn it_converts_to_icu_types() {
+    assert_eq!(
+        super::bcp47(chrono_tz::Europe::Paris).map(|id| id.0),
+        Some("frpar".parse().unwrap())
+    );
+    assert_eq!(
+        super::bcp47(chrono_tz::Asia::Calcutta),
+        super::bcp47(chrono_tz::Asia::Kolkata)
+    );
+
+    let custom = super::system_custom_tz().unwrap();
+    assert!(custom.gmt_offset.is_some());
+    assert_eq!(custom.time_zone_id, super::system_tz_bcp47());
+}
+
+#[test]
+#[cfg(all(feature = "tz-rs", target_family = "unix"))]
+fn it_parses_localtime_rules() {
+    if std::path::Path::new("/etc/localtime").exists() {
+        let tz = super::system_tz_rs().unwrap();
+        assert!(tz.find_current_local_time_type().is_ok());
+    }
+}
+
+#[test]
+fn it_tracks_the_system_timezone() {
+    use chrono::{Offset, TimeZone};
+
+    let utc = chrono::Utc::now().naive_utc();
+    let tz = super::SystemLocal::tz();
+    assert_eq!(tz, super::SystemLocal::tz());
+    assert_eq!(
+        super::SystemLocal.offset_from_utc_datetime(&utc).fix(),
+        tz.offset_from_utc_datetime(&utc).fix()
+    );
+    assert_eq!(
+        super::SystemLocal::now().offset().fix(),
+        tz.offset_from_utc_datetime(&utc).fix()
+    );
+}
+
+#[test]
+#[cfg(feature = "capi")]
+fn it_exports_a_c_api() {
+    use super::capi::{system_tz_free, system_tz_name};
+
+    let name = system_tz_name();
+    let value = (!name.is_null()).then(|| {
+        let value = unsafe { std::ffi::CStr::from_ptr(name) };
+        value.to_str().unwrap().to_owned()
+    });
+    unsafe { system_tz_free(name) };
+    assert_eq!(value, super::system_tz_name());
+
+    assert_eq!(
+        include_str!("../include/system_tz.h"),
+        include_str!(concat!(env!("OUT_DIR"), "/system_tz.h")),
+        "Outdated header, run `cbindgen --output include/system_tz.h`"
+    );
+}
+
+#[test]
+#[cfg(feature = "uniffi")]
+fn it_exposes_a_uniffi_api() {
+    use super::uniffi_api::{guess_tz, iana_to_windows, system_tz_name, windows_to_iana};
+
+    assert_eq!(system_tz_name(), super::system_tz_name());
+    assert_eq!(guess_tz(9 * 3600, Some("JP".into())), ["Asia/Tokyo"]);
+    assert!(guess_tz(i32::MAX, None).is_empty());
+    assert_eq!(
+        windows_to_iana("Romance Standard Time", Some("FR".into())),
+        Some("Europe/Paris".into())
+    );
+    assert_eq!(
+        iana_to_windows("Europe/Paris"),
+        Some("Romance Standard Time".into())
+    );
+    assert_eq!(iana_to_windows("Not/A_Zone"), None);
+}
+
+#[test]
+#[cfg(feature = "napi")]
+fn it_looks_up_node_api_in_node_only() {
+    assert!(super::napi::Api::get().is_none());
+}
+
+#[test]
+#[cfg(any(target_family = "unix", target_family = "windows"))]
+fn it_shares_the_system_timezone() {
+    use super::SystemTzCell;
+
+    let cell = SystemTzCell::new();
+    assert_eq!(cell.get(), super::system_tz());
+
+    let handle = cell.clone();
+    let changes = handle.subscribe();
+    let dropped = cell.subscribe();
+    drop(dropped);
+    cell.shared.update(Some(chrono_tz::Asia::Tokyo));
+    assert_eq!(handle.get(), Some(chrono_tz::Asia::Tokyo));
+    assert_eq!(changes.try_recv(), Ok(Some(chrono_tz::Asia::Tokyo)));
+    assert_eq!(
+        cell.shared.subscribers.lock().map(|x| x.len()).ok(),
+        Some(1)
+    );
+}
+
+#[test]
+fn it_computes_dst_information() {
+    use super::SystemTzInfo;
+    use chrono::TimeZone;
+
+    let paris = SystemTzInfo::new(chrono_tz::Europe::Paris);
+    let winter = chrono::Utc.with_ymd_and_hms(2023, 1, 15, 12, 0, 0).unwrap();
+    let summer = chrono::Utc.with_ymd_and_hms(2023, 7, 15, 12, 0, 0).unwrap();
+    assert!(!paris.is_dst_at(winter));
+    assert!(paris.is_dst_at(summer));
+    assert_eq!(paris.abbreviation_at(winter), "CET");
+    assert_eq!(paris.abbreviation_at(summer), "CEST");
+    assert_eq!(paris.offset_at(summer).local_minus_utc(), 7200);
+    assert_eq!(
+        paris
+            .next_transition_after(winter)
+            .map(|at| at.to_rfc3339()),
+        Some("2023-03-26T03:00:00+02:00".into())
+    );
+    assert_eq!(
+        paris
+            .next_transition_after(summer)
+            .map(|at| at.to_rfc3339()),
+        Some("2023-10-29T02:00:00+01:00".into())
+    );
+
+    let tokyo = SystemTzInfo::new(chrono_tz::Asia::Tokyo);
+    assert_eq!(tokyo.next_transition_after(summer), None);
+    assert!(!tokyo.is_dst_now());
+}
+
+#[test]
+fn it_generates_posix_tz_strings() {
+    use super::to_posix_tz;
+    use chrono_tz::{America, Asia, Australia, Europe};
+
+    for (tz, posix) in [
+        (Europe::Paris, "CET-1CEST,M3.5.0,M10.5.0/3"),
+        (Europe::London, "GMT0BST,M3.5.0/1,M10.5.0"),
+        (America::New_York, "EST5EDT,M3.2.0,M11.1.0"),
+        (Australia::Sydney, "AEST-10AEDT,M10.1.0,M4.1.0/3"),
+        (Australia::Lord_Howe, "<+1030>-10:30<+11>-11,M10.1.0,M4.1.0"),
+        (America::Sao_Paulo, "<-03>3"),
+        (Asia::Tokyo, "JST-9"),
+        (Asia::Kathmandu, "<+0545>-5:45"),
+        (chrono_tz::UTC, "UTC0"),
+    ] {
+        assert_eq!(to_posix_tz(tz, 2023).as_deref(), Some(posix), "{tz}");
+    }
+
+    // Rules are spelled as they fall in the given year
+    assert_eq!(
+        to_posix_tz(America::Santiago, 2023).as_deref(),
+        Some("<-04>4<-03>,M9.1.0/0,M4.1.0/0")
+    );
+    assert_eq!(
+        to_posix_tz(America::Santiago, 2024).as_deref(),
+        Some("<-04>4<-03>,M9.2.0/0,M4.1.0/0")
+    );
+}
+
+#[test]
+fn it_extracts_locale_territories() {
+    use super::locale_territory;
+
+    assert_eq!(locale_territory("fr_FR.UTF-8"), Some("FR".into()));
+    assert_eq!(locale_territory("de_DE@euro"), Some("DE".into()));
+    assert_eq!(locale_territory("en-us"), Some("US".into()));
+    assert_eq!(locale_territory("zh-Hant-TW"), Some("TW".into()));
+    assert_eq!(locale_territory("es-419"), None);
+    assert_eq!(locale_territory("C.UTF-8"), None);
+    assert_eq!(locale_territory("POSIX"), None);
+}
+
+#[test]
+#[cfg(feature = "log")]
+fn it_logs_probes() {
+    use super::ProviderChain;
+
+    struct Logger(std::sync::Mutex<Vec<String>>);
+
+    impl log::Log for Logger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.target() == "system_tz"
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: Logger = Logger(std::sync::Mutex::new(Vec::new()));
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Debug);
+
+    let chain = ProviderChain::new().with(Stub::custom("fixed").raw("Europe/Paris"));
+    assert_eq!(chain.detect(), Ok(chrono_tz::Europe::Paris));
+    assert!(LOGGER
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|event| event.contains("fixed") && event.contains("Europe/Paris")));
 }
@@ -0,0 +1,11 @@
+//! Generates the Kotlin and Swift bindings of the `uniffi` feature, e.g.:
+//!
+//! ```sh
+//! cargo rustc --lib --release --crate-type cdylib --features uniffi
+//! cargo run --features uniffi-cli --bin uniffi-bindgen -- generate \
+//!     --library target/release/libsystem_tz.so --language kotlin --out-dir bindings
+//! ```
+
+fn main() {
+    uniffi::uniffi_bindgen_main();
+}
@@ -0,0 +1,106 @@
+//! Runs the `tz` binary with the timezone pinned by `SYSTEM_TZ`
+//! (ignored with the `hardened` feature).
+#![cfg(all(feature = "cli", not(feature = "hardened")))]
+
+use std::process::{Command, Output};
+
+/// Runs `tz` with `args`, `SYSTEM_TZ` being set to `tz`.
+fn tz(tz: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tz"))
+        .args(args)
+        .env("SYSTEM_TZ", tz)
+        .output()
+        .expect("Failed to run `tz`")
+}
+
+/// Returns the exit code and the standard output of `tz` with `args` in `Europe/Paris`.
+fn paris(args: &[&str]) -> (Option<i32>, String) {
+    let output = tz("Europe/Paris", args);
+    let stdout = String::from_utf8(output.stdout).expect("Invalid UTF-8 output");
+    (output.status.code(), stdout)
+}
+
+#[test]
+fn it_prints_the_system_timezone() {
+    assert_eq!(paris(&[]), (Some(0), "Europe/Paris\n".into()));
+
+    let (code, json) = paris(&["--json"]);
+    assert_eq!(code, Some(0));
+    assert!(json.starts_with(r#"{"tz":"Europe/Paris","source":"env_system_tz","#));
+
+    assert_eq!(paris(&["--jsno"]).0, Some(2));
+    assert_eq!(paris(&["--help"]).0, Some(0));
+}
+
+#[test]
+fn it_reads_points_in_time() {
+    assert_eq!(
+        paris(&["offset", "--at", "2024-07-01 12:00"]),
+        (Some(0), "+02:00\n".into())
+    );
+    assert_eq!(
+        paris(&["abbr", "--at=2024-01-01T00:00:00Z"]),
+        (Some(0), "CET\n".into())
+    );
+    assert_eq!(paris(&["offset", "--at"]).0, Some(2));
+    assert_eq!(paris(&["offset", "--at", "tomorrow"]).0, Some(2));
+    assert_eq!(paris(&["abbr", "--in", "2024"]).0, Some(2));
+
+    assert_eq!(
+        paris(&["convert", "2024-03-10 09:00", "America/New_York"]),
+        (Some(0), "2024-03-10 14:00:00 CET\n".into())
+    );
+    assert_eq!(
+        paris(&["convert", "2024-03-10 09:00", "Mars/Tharsis"]).0,
+        Some(2)
+    );
+}
+
+#[test]
+fn it_lists_and_validates_timezones() {
+    let (code, zones) = paris(&["list", "--country", "FR"]);
+    assert_eq!(code, Some(0));
+    assert!(zones.lines().any(|tz| tz == "Europe/Paris"));
+    assert_eq!(paris(&["list", "--country", "XX"]).0, Some(1));
+    assert_eq!(paris(&["list", "--country"]).0, Some(2));
+
+    assert_eq!(
+        paris(&["validate", "Europe/Paris"]),
+        (Some(0), "Europe/Paris: valid, canonical\n".into())
+    );
+    assert_eq!(paris(&["validate", "US/Eastern"]).0, Some(0));
+    assert_eq!(paris(&["validate", "US/Eastern", "--strict"]).0, Some(1));
+    assert_eq!(paris(&["validate", "europe/paris"]).0, Some(1));
+    assert_eq!(paris(&["validate"]).0, Some(2));
+
+    let (code, found) = paris(&["search", "pari"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(found.lines().next(), Some("Europe/Paris"));
+}
+
+#[test]
+fn it_maps_windows_zones() {
+    assert_eq!(
+        paris(&["windows", "Romance Standard Time"]),
+        (Some(0), "Europe/Paris\n".into())
+    );
+    assert_eq!(
+        paris(&["windows", "Europe/Paris", "--reverse"]),
+        (Some(0), "Romance Standard Time\n".into())
+    );
+    assert_eq!(paris(&["windows", "Mars Standard Time"]).0, Some(1));
+    assert_eq!(paris(&["windows"]).0, Some(2));
+}
+
+#[test]
+fn it_runs_the_doctor() {
+    let (code, report) = paris(&["doctor"]);
+    assert_eq!(code, Some(0));
+    assert!(report.contains("Europe/Paris"));
+    assert_eq!(paris(&["doctor", "--help"]).0, Some(0));
+    assert_eq!(paris(&["doctor", "--verbose"]).0, Some(2));
+
+    let output = tz("Mars/Tharsis", &["doctor"]);
+    let report = String::from_utf8(output.stdout).expect("Invalid UTF-8 output");
+    assert!(report.contains("`Mars/Tharsis` read from SYSTEM_TZ isn't a known timezone"));
+}